@@ -0,0 +1,69 @@
+use eframe::egui;
+
+pub const KINDS: [&str; 3] = ["Status equals", "Body contains", "Header present"];
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Assertion {
+    pub kind: String,
+    pub value: String,
+}
+
+impl Assertion {
+    // A failed request has no real status, headers or body, so nothing passes against it
+    pub fn passes(&self, status: &str, headers: &[String], body: &str, failed: bool) -> bool {
+        if failed {
+            return false;
+        }
+        let value = self.value.trim();
+        match self.kind.as_str() {
+            "Status equals" => status.split_whitespace().next() == Some(value),
+            "Body contains" => body.contains(&self.value),
+            "Header present" => headers.iter().any(|header| header.split_once(':').is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case(value))),
+            _ => false,
+        }
+    }
+}
+
+// Lists the assertions with a check or cross each and edits the list in place
+pub fn show(ui: &mut egui::Ui, id: usize, assertions: &mut Vec<Assertion>, results: &[bool], new_kind: &mut String, new_value: &mut String) {
+    let mut remove = None;
+    for (position, (assertion, passed)) in assertions.iter().zip(results).enumerate() {
+        ui.horizontal(|ui| {
+            if *passed {
+                ui.colored_label(egui::Color32::GREEN, "✔");
+            }
+            else {
+                ui.colored_label(egui::Color32::RED, "✘");
+            }
+            ui.label(format!("{} {}", assertion.kind, assertion.value));
+            if ui.small_button("Remove").clicked() {
+                remove = Some(position);
+            }
+        });
+    }
+    if let Some(position) = remove {
+        assertions.remove(position);
+    }
+
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt(("assertion_kind", id))
+            .selected_text(new_kind.as_str())
+            .show_ui(ui, |ui| {
+                for kind in KINDS {
+                    ui.selectable_value(new_kind, kind.to_string(), kind);
+                }
+            });
+        let hint = match new_kind.as_str() {
+            "Status equals" => "200",
+            "Header present" => "Content-Type",
+            _ => "\"id\":",
+        };
+        ui.add(egui::TextEdit::singleline(new_value).desired_width(150.0).hint_text(hint));
+        if ui.add_enabled(!new_value.trim().is_empty(), egui::Button::new("Add")).clicked() {
+            assertions.push(Assertion {
+                kind: new_kind.clone(),
+                value: std::mem::take(new_value),
+            });
+        }
+    });
+}
@@ -0,0 +1,108 @@
+use reqwest::RequestBuilder;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct Auth {
+    pub auth_type: String,
+    pub username: String,
+    pub password: String,
+    pub token: String,
+}
+
+impl Auth {
+    // Basic and Bearer go out on the first request, Digest waits for the server's challenge
+    pub fn apply(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self.auth_type.as_str() {
+            "Basic" => builder.basic_auth(&self.username, Some(&self.password)),
+            "Bearer" => builder.bearer_auth(&self.token),
+            _ => builder,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: String,
+    pub qop: Option<String>,
+}
+
+pub fn parse_digest_challenge(header: &str) -> Option<DigestChallenge> {
+    let params = header.trim().strip_prefix("Digest")?.trim();
+    let mut challenge = DigestChallenge {
+        algorithm: "MD5".to_string(),
+        ..Default::default()
+    };
+
+    for (key, value) in split_params(params) {
+        match key.to_ascii_lowercase().as_str() {
+            "realm" => challenge.realm = value,
+            "nonce" => challenge.nonce = value,
+            "opaque" => challenge.opaque = Some(value),
+            "algorithm" => challenge.algorithm = value,
+            // Servers may offer "auth,auth-int", only auth is supported
+            "qop" if value.split(',').any(|q| q.trim() == "auth") => challenge.qop = Some("auth".to_string()),
+            _ => (),
+        }
+    }
+
+    if challenge.nonce.is_empty() {
+        return None;
+    }
+    Some(challenge)
+}
+
+pub fn digest_authorization(challenge: &DigestChallenge, username: &str, password: &str, method: &str, uri: &str) -> Result<String, String> {
+    let nc = "00000001";
+    let cnonce = format!("{:x}", md5::compute(format!("{:?}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default())));
+
+    let mut ha1 = format!("{:x}", md5::compute(format!("{}:{}:{}", username, challenge.realm, password)));
+    match challenge.algorithm.to_ascii_uppercase().as_str() {
+        "MD5" => (),
+        "MD5-SESS" => ha1 = format!("{:x}", md5::compute(format!("{}:{}:{}", ha1, challenge.nonce, cnonce))),
+        other => return Err(format!("Unsupported digest algorithm: {}", other)),
+    }
+    let ha2 = format!("{:x}", md5::compute(format!("{}:{}", method, uri)));
+
+    let response = match &challenge.qop {
+        Some(qop) => format!("{:x}", md5::compute(format!("{}:{}:{}:{}:{}:{}", ha1, challenge.nonce, nc, cnonce, qop, ha2))),
+        None => format!("{:x}", md5::compute(format!("{}:{}:{}", ha1, challenge.nonce, ha2))),
+    };
+
+    let mut header = format!(
+        "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+        username, challenge.realm, challenge.nonce, uri, challenge.algorithm, response
+    );
+    if let Some(qop) = &challenge.qop {
+        header.push_str(&format!(", qop={}, nc={}, cnonce=\"{}\"", qop, nc, cnonce));
+    }
+    if let Some(opaque) = &challenge.opaque {
+        header.push_str(&format!(", opaque=\"{}\"", opaque));
+    }
+
+    Ok(header)
+}
+
+// Splits `key="value", key=value` pairs, keeping commas inside quotes
+fn split_params(params: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in params.chars().chain(std::iter::once(',')) {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                if let Some((key, value)) = current.split_once('=') {
+                    pairs.push((key.trim().to_string(), value.trim().to_string()));
+                }
+                current.clear();
+            },
+            _ => current.push(c),
+        }
+    }
+
+    pairs
+}
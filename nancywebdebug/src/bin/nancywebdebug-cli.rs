@@ -0,0 +1,242 @@
+use nancywebdebug::{auth, request};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: nancywebdebug-cli [OPTIONS] <URL>
+
+Options:
+  -X, --method <METHOD>     Request method (default: GET)
+  -H, --header <HEADER>     Request header as \"Name: value\", may be repeated
+  -d, --body <BODY>         Request body, or @path to stream it from a file
+      --auth <TYPE>         None, Basic, Bearer or Digest (default: None)
+  -u, --user <USER:PASS>    Credentials for Basic or Digest auth
+      --token <TOKEN>       Token for Bearer auth
+      --dns <PROVIDER>      System, Cloudflare or Google (default: System)
+      --exact-headers       Don't add a User-Agent header
+      --raw-chunks          Show chunk-size lines in a chunked probe response
+      --probe-retries <N>   Probe connection retries on timeout/refusal (default: 2)
+      --probe-delay <MS>    Delay between probe retries (default: 500)
+      --skip-probe          Skip the DNS, TCP and TLS checks before sending
+      --max-size <MB>       Stop reading the response past this size, 0 for no limit (default: 100)
+      --delay <MS>          Wait this long before sending, to simulate latency
+      --throttle <BYTES>    Read the response body at most this many bytes per second
+      --cacert <PATH>       Also trust the root CA certificates in this PEM file
+      --https-only          Refuse plain HTTP and never fall back to the Permissive client
+      --redirect-bodies     Print each redirect response followed, body included, to stderr
+      --default-scheme <S>  For a URL without one: http, https or require (default: http)
+      --proxy <URL>         Send the request through this proxy, e.g. localhost:8080
+      --tls-min <VERSION>   Minimum TLS version: 1.0, 1.1, 1.2 or 1.3
+      --tls-max <VERSION>   Maximum TLS version: 1.0, 1.1, 1.2 or 1.3
+      --first-client <TIER> Client to start at: Standard, Permissive or \"Legacy TLS\"
+  -v, --verbose             Print diagnostics to stderr on success too
+  -h, --help                Print this help
+
+Exit codes: 0 success, 1 HTTP error status (4xx/5xx), 2 request failed, 64 usage error";
+
+struct Args {
+    method: String,
+    url: String,
+    headers: Vec<String>,
+    body: String,
+    auth: auth::Auth,
+    options: request::RequestOptions,
+    verbose: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        method: "GET".to_string(),
+        url: String::new(),
+        headers: Vec::new(),
+        body: String::new(),
+        auth: auth::Auth {
+            auth_type: "None".to_string(),
+            username: String::new(),
+            password: String::new(),
+            token: String::new(),
+        },
+        options: request::RequestOptions {
+            dns_provider: "System".to_string(),
+            exact_headers: false,
+            pool_max_idle_per_host: request::DEFAULT_POOL_MAX_IDLE,
+            raw_chunk_framing: false,
+            probe_retries: request::DEFAULT_PROBE_RETRIES,
+            probe_retry_delay_ms: request::DEFAULT_PROBE_RETRY_DELAY_MS,
+            skip_probe: false,
+            max_response_mb: request::DEFAULT_MAX_RESPONSE_MB,
+            drop_bodyless_body: false,
+            body_file: String::new(),
+            upload_progress: None,
+            send_delay_ms: 0,
+            throttle_bytes_per_sec: 0,
+            root_ca_file: String::new(),
+            https_only: false,
+            redirect_hops: None,
+            masked_headers: Vec::new(),
+            shown_body: None,
+            proxy: String::new(),
+            event_stream: None,
+            tls_min: String::new(),
+            tls_max: String::new(),
+            first_client: String::new(),
+        },
+        verbose: false,
+    };
+
+    let mut default_scheme = request::DEFAULT_SCHEMES[0].to_string();
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = |name: &str| argv.next().ok_or_else(|| format!("Missing value for {}", name));
+        match arg.as_str() {
+            "-X" | "--method" => args.method = value(&arg)?.to_uppercase(),
+            "-H" | "--header" => args.headers.push(value(&arg)?),
+            "-d" | "--body" => {
+                let body = value(&arg)?;
+                match body.strip_prefix('@') {
+                    Some(path) => args.options.body_file = path.to_string(),
+                    None => args.body = body,
+                }
+            },
+            "--auth" => args.auth.auth_type = capitalize(&value(&arg)?),
+            "-u" | "--user" => {
+                let user = value(&arg)?;
+                let (username, password) = user.split_once(':').unwrap_or((&user, ""));
+                args.auth.username = username.to_string();
+                args.auth.password = password.to_string();
+            },
+            "--token" => args.auth.token = value(&arg)?,
+            "--dns" => args.options.dns_provider = capitalize(&value(&arg)?),
+            "--exact-headers" => args.options.exact_headers = true,
+            "--raw-chunks" => args.options.raw_chunk_framing = true,
+            "--probe-retries" => args.options.probe_retries = parse_number(&arg, &value(&arg)?)?,
+            "--proxy" => args.options.proxy = value(&arg)?,
+            "--tls-min" | "--tls-max" => {
+                let version = value(&arg)?;
+                if !request::TLS_VERSIONS.contains(&version.as_str()) {
+                    return Err(format!("Unknown TLS version for {}: {}, expected one of {}", arg, version, request::TLS_VERSIONS.join(", ")));
+                }
+                match arg.as_str() {
+                    "--tls-min" => args.options.tls_min = version,
+                    _ => args.options.tls_max = version,
+                }
+            },
+            "--first-client" => {
+                let tier = value(&arg)?;
+                args.options.first_client = request::CLIENT_TIERS.iter()
+                    .find(|known| known.eq_ignore_ascii_case(&tier))
+                    .ok_or_else(|| format!("Unknown client: {}", tier))?
+                    .to_string();
+            },
+            "--skip-probe" => args.options.skip_probe = true,
+            "--max-size" => args.options.max_response_mb = parse_number(&arg, &value(&arg)?)?,
+            "--delay" => args.options.send_delay_ms = parse_number(&arg, &value(&arg)?)?,
+            "--throttle" => args.options.throttle_bytes_per_sec = parse_number(&arg, &value(&arg)?)?,
+            "--cacert" => args.options.root_ca_file = value(&arg)?,
+            "--https-only" => args.options.https_only = true,
+            "--redirect-bodies" => args.options.redirect_hops = Some(Default::default()),
+            "--default-scheme" => {
+                let scheme = value(&arg)?;
+                default_scheme = request::DEFAULT_SCHEMES.iter()
+                    .find(|known| known.eq_ignore_ascii_case(&scheme))
+                    .ok_or_else(|| format!("Unknown default scheme: {}", scheme))?
+                    .to_string();
+            },
+            "--probe-delay" => args.options.probe_retry_delay_ms = parse_number(&arg, &value(&arg)?)?,
+            "-v" | "--verbose" => args.verbose = true,
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+            _ if args.url.is_empty() => args.url = arg,
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    if args.url.is_empty() {
+        return Err("URL is empty".to_string());
+    }
+    args.url = request::apply_default_scheme(&args.url, &default_scheme)?;
+    Ok(args)
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid number for {}: {}", name, value))
+}
+
+// Accepts "digest" or "DIGEST" for the "Digest" names the GUI uses
+fn capitalize(value: &str) -> String {
+    let lower = value.to_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => lower,
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("{}\n", e);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(64);
+        }
+    };
+
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build() {
+            Ok(rt) => rt,
+            Err(e) => {
+                eprintln!("Error building tokio runtime: {}", e);
+                return ExitCode::from(2);
+            }
+        };
+
+    let request_url = request::normalize_url(&args.url);
+    let redirect_hops = args.options.redirect_hops.clone();
+    let result = rt.block_on(request::send_request(args.method, request_url, args.headers.join("\n"), args.body, args.auth, args.options));
+    // Kept apart from stdout, which only ever holds the final response
+    for hop in redirect_hops.map(|hops| hops.lock().unwrap().clone()).unwrap_or_default() {
+        eprintln!("{} {}\n{}\n\n{}\n", hop.status, hop.url, hop.headers.join("\n"), hop.body);
+    }
+
+    match result {
+        Ok(request::Response { status, headers, body, trace, raw_body, body_error, .. }) => {
+            if args.verbose {
+                eprintln!("{}", trace);
+            }
+            println!("{}", status);
+            for header in headers {
+                println!("{}", header);
+            }
+            println!();
+            // Binary bodies and other charsets go out byte for byte, like curl
+            match raw_body {
+                Some(bytes) => {
+                    if let Err(e) = std::io::Write::write_all(&mut std::io::stdout(), &bytes) {
+                        eprintln!("Unable to write body: {}", e);
+                    }
+                },
+                None => println!("{}", body),
+            }
+            // What arrived is printed, but it isn't the whole body
+            if let Some(e) = body_error {
+                eprintln!("{}", e);
+                return ExitCode::from(2);
+            }
+
+            let status_code = status.split_whitespace().next().and_then(|code| code.parse::<u16>().ok()).unwrap_or(0);
+            if status_code >= 400 {
+                ExitCode::from(1)
+            }
+            else {
+                ExitCode::SUCCESS
+            }
+        },
+        Err((e, status, _headers, tracebuilder)) => {
+            eprintln!("{}: {}\n", status, e);
+            eprintln!("{}", tracebuilder);
+            ExitCode::from(2)
+        }
+    }
+}
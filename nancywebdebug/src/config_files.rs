@@ -0,0 +1,29 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+
+// Environments and saved requests live as pretty JSON next to eframe's own storage, so they can be
+// edited by hand or checked in and shared
+pub fn dir() -> Option<PathBuf> {
+    eframe::storage_dir(crate::APP_NAME)
+}
+
+// None when the file doesn't exist yet, an Err names the file and where parsing failed
+pub fn load<T: DeserializeOwned>(name: &str) -> Result<Option<T>, String> {
+    let Some(path) = dir().map(|dir| dir.join(name)) else { return Ok(None) };
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Unable to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&text).map(Some).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) -> Result<(), String> {
+    let Some(dir) = dir() else { return Ok(()) };
+    let path = dir.join(name);
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&path, text + "\n"))
+        .map_err(|e| format!("Unable to write {}: {}", path.display(), e))
+}
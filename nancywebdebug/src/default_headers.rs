@@ -0,0 +1,120 @@
+use eframe::egui;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HeaderSet {
+    // Empty (or *) for every host, an exact host, or *.example.com for its subdomains
+    pub host: String,
+    // "Name: value" lines like the request headers box, {{name}} placeholders included
+    #[serde(default)]
+    pub headers: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl HeaderSet {
+    fn global(&self) -> bool {
+        matches!(self.host.trim(), "" | "*")
+    }
+
+    // How an applied set is listed with the request it went out on
+    pub fn label(&self) -> String {
+        if self.global() { "All hosts".to_string() } else { self.host.trim().to_string() }
+    }
+
+    fn matches(&self, host: &str) -> bool {
+        let pattern = self.host.trim().to_ascii_lowercase();
+        let host = host.to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host.ends_with(&format!(".{}", domain)),
+            None => self.global() || pattern == host,
+        }
+    }
+}
+
+// The enabled sets that apply to the URL's host, global ones first so a host set can override them
+pub fn matching<'a>(sets: &'a [HeaderSet], url: &str) -> Vec<&'a HeaderSet> {
+    let Some(host) = url::Url::parse(url).ok().and_then(|url| url.host_str().map(|host| host.to_string())) else { return Vec::new() };
+    let (mut global, scoped): (Vec<&HeaderSet>, Vec<&HeaderSet>) = sets.iter()
+        .filter(|set| set.enabled && set.matches(&host))
+        .partition(|set| set.global());
+    global.extend(scoped);
+    global
+}
+
+// Each layer's lines replace any earlier line with the same name, so host sets go over global ones and
+// the request's own headers over both. Merging headers that already hold the defaults changes nothing,
+// so a resent entry doesn't pick up duplicates
+pub fn merge<'a>(layers: impl IntoIterator<Item = &'a str>) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for layer in layers {
+        let layer: Vec<&str> = layer.lines().filter(|line| !line.trim().is_empty()).collect();
+        let names: Vec<String> = layer.iter().map(|line| name(line)).collect();
+        lines.retain(|line| !names.contains(&name(line)));
+        lines.extend(layer);
+    }
+    lines.join("\n")
+}
+
+// Header names the sets add to the request's own headers, for showing before it's sent
+pub fn added_names(sets: &[&HeaderSet], request_headers: &str) -> Vec<String> {
+    let typed: Vec<String> = request_headers.lines().map(name).collect();
+    let mut names: Vec<String> = Vec::new();
+    for line in sets.iter().flat_map(|set| set.headers.lines()).filter(|line| !line.trim().is_empty()) {
+        let added = line.split_once(':').map(|(name, _)| name).unwrap_or(line).trim().to_string();
+        if !typed.contains(&name(line)) && !names.iter().any(|existing| existing.eq_ignore_ascii_case(&added)) {
+            names.push(added);
+        }
+    }
+    names
+}
+
+fn name(line: &str) -> String {
+    line.split_once(':').map(|(name, _)| name).unwrap_or(line).trim().to_ascii_lowercase()
+}
+
+pub fn show_manager(ctx: &egui::Context, open: &mut bool, sets: &mut Vec<HeaderSet>) {
+    egui::Window::new("Default Headers")
+        .open(open)
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label("Added to every request whose host matches. Host sets override global ones, headers typed for the request override both.");
+            ui.add_space(5.0);
+            let mut remove = None;
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("default_headers_grid").striped(true).show(ui, |ui| {
+                    ui.label("On");
+                    ui.label("Host");
+                    ui.label("Headers");
+                    ui.end_row();
+
+                    for (position, set) in sets.iter_mut().enumerate() {
+                        ui.checkbox(&mut set.enabled, "");
+                        ui.add(egui::TextEdit::singleline(&mut set.host).desired_width(160.0).hint_text("All hosts"))
+                            .on_hover_text("api.example.com, or *.internal.corp for every subdomain. Empty applies to every host");
+                        ui.add(egui::TextEdit::multiline(&mut set.headers).desired_width(300.0).desired_rows(2).hint_text("X-Internal-Token: {{internal_token}}"));
+                        if ui.small_button("Remove").clicked() {
+                            remove = Some(position);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+            if let Some(position) = remove {
+                sets.remove(position);
+            }
+
+            ui.add_space(10.0);
+            if ui.button("Add Header Set").clicked() {
+                sets.push(HeaderSet {
+                    host: String::new(),
+                    headers: String::new(),
+                    enabled: true,
+                });
+            }
+        });
+}
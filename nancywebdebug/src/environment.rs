@@ -0,0 +1,101 @@
+use eframe::egui;
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Environment {
+    pub name: String,
+    pub base_url: String,
+    // Name and value pairs for {{name}} placeholders
+    #[serde(default)]
+    pub variables: Vec<(String, String)>,
+}
+
+impl Environment {
+    pub fn set_variable(&mut self, name: &str, value: String) {
+        match self.variables.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.variables.push((name.to_string(), value)),
+        }
+    }
+}
+
+pub fn find<'a>(environments: &'a [Environment], name: &str) -> Option<&'a Environment> {
+    environments.iter().find(|environment| environment.name == name)
+}
+
+// Replaces {{name}} with the environment's value, unknown names are left as typed so they show up in the sent request
+pub fn substitute(text: &str, environment: Option<&Environment>) -> String {
+    let Some(environment) = environment else { return text.to_string() };
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else { break };
+        let name = &rest[start + 2..start + 2 + length];
+        result.push_str(&rest[..start]);
+        match environment.variables.iter().find(|(variable, _)| variable == name.trim()) {
+            Some((_, value)) => result.push_str(value),
+            None => result.push_str(&rest[start..start + length + 4]),
+        }
+        rest = &rest[start + length + 4..];
+    }
+    result.push_str(rest);
+    result
+}
+
+pub fn show_manager(ctx: &egui::Context, open: &mut bool, environments: &mut Vec<Environment>) {
+    egui::Window::new("Environments")
+        .open(open)
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            let mut remove = None;
+            egui::Grid::new("environments_grid").striped(true).show(ui, |ui| {
+                ui.label("Name");
+                ui.label("Base URL");
+                ui.end_row();
+
+                for (position, environment) in environments.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(&mut environment.name).desired_width(120.0));
+                    ui.add(egui::TextEdit::singleline(&mut environment.base_url).desired_width(300.0).hint_text("https://api.example.com/v1/"));
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(position);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(position) = remove {
+                environments.remove(position);
+            }
+
+            for (position, environment) in environments.iter_mut().enumerate() {
+                egui::CollapsingHeader::new(format!("{} variables ({})", environment.name, environment.variables.len()))
+                    .id_salt(("environment_variables", position))
+                    .show(ui, |ui| {
+                        let mut remove = None;
+                        egui::Grid::new(("environment_variables_grid", position)).striped(true).show(ui, |ui| {
+                            for (variable, (name, value)) in environment.variables.iter_mut().enumerate() {
+                                ui.add(egui::TextEdit::singleline(name).desired_width(120.0));
+                                ui.add(egui::TextEdit::singleline(value).desired_width(300.0));
+                                if ui.small_button("Remove").clicked() {
+                                    remove = Some(variable);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                        if let Some(variable) = remove {
+                            environment.variables.remove(variable);
+                        }
+                        if ui.small_button("Add Variable").on_hover_text("Use as {{name}} in the URL, headers or body").clicked() {
+                            environment.variables.push((format!("variable{}", environment.variables.len() + 1), String::new()));
+                        }
+                    });
+            }
+
+            ui.add_space(10.0);
+            if ui.button("Add Environment").clicked() {
+                environments.push(Environment {
+                    name: format!("Environment {}", environments.len() + 1),
+                    ..Default::default()
+                });
+            }
+        });
+}
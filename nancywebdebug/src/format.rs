@@ -0,0 +1,634 @@
+use base64::Engine;
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+
+pub fn is_json(body: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(body).is_ok()
+}
+
+pub fn pretty_json(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    serde_json::to_string_pretty(&value).ok()
+}
+
+pub fn minify_json(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    serde_json::to_string(&value).ok()
+}
+
+pub fn to_base64(body: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(body)
+}
+
+pub fn validate_json(body: &str) -> Result<(), String> {
+    serde_json::from_str::<serde_json::Value>(body)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+pub fn validate_xml(body: &str) -> Result<(), String> {
+    let mut reader = Reader::from_str(body);
+    let mut open_tags: Vec<String> = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(tag)) => open_tags.push(String::from_utf8_lossy(tag.name().as_ref()).to_string()),
+            Ok(Event::End(_)) => {
+                open_tags.pop();
+            },
+            Ok(Event::Eof) => break,
+            Ok(_) => (),
+            Err(e) => return Err(format!("Position {}: {}", reader.error_position(), e)),
+        }
+    }
+
+    match open_tags.last() {
+        Some(tag) => Err(format!("Unclosed tag <{}>", tag)),
+        None => Ok(()),
+    }
+}
+
+pub fn pretty_xml(body: &str) -> Option<String> {
+    let mut reader = Reader::from_str(body);
+    reader.config_mut().trim_text(true);
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+    loop {
+        match reader.read_event().ok()? {
+            Event::Eof => break,
+            event => writer.write_event(event).ok()?,
+        }
+    }
+
+    String::from_utf8(writer.into_inner()).ok()
+}
+
+pub fn parse_form(body: &str) -> Vec<(String, String)> {
+    url::form_urlencoded::parse(body.trim().as_bytes())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+// Looks up a header from the "Name: value" lines used throughout the UI
+pub fn header_value(headers: &str, name: &str) -> Option<String> {
+    headers.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().to_string())
+}
+
+// Replaces every line of the header with one `name: value` where the first stood (appended if there
+// was none), or removes them all for None. Other lines are left exactly as typed
+pub fn set_header(headers: &str, name: &str, value: Option<&str>) -> String {
+    let mut lines = Vec::new();
+    let mut replaced = false;
+    for line in headers.lines() {
+        if line.split_once(':').is_some_and(|(key, _)| key.trim().eq_ignore_ascii_case(name)) {
+            if let Some(value) = value.filter(|_| !replaced) {
+                lines.push(format!("{}: {}", name, value));
+            }
+            replaced = true;
+        }
+        else {
+            lines.push(line.to_string());
+        }
+    }
+    if let Some(value) = value.filter(|_| !replaced) {
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        lines.push(format!("{}: {}", name, value));
+    }
+    lines.join("\n")
+}
+
+// Display-only formatting, the stored body is never rewritten
+pub fn format_body(view: &str, body: &str) -> String {
+    match view {
+        "Pretty" => pretty_json(body).unwrap_or_else(|| body.to_string()),
+        "Minified" => minify_json(body).unwrap_or_else(|| body.to_string()),
+        _ => body.to_string(),
+    }
+}
+
+// What Auto rendering treats the body as, the Content-Type first and then the body itself
+// since servers often send text as application/octet-stream or leave the header out
+pub fn sniff_kind(content_type: &str, body: &str) -> &'static str {
+    let content_type = content_type.to_ascii_lowercase();
+    let start = body.trim_start().get(..64).unwrap_or(body.trim_start()).to_ascii_lowercase();
+    if content_type.contains("markdown") {
+        "Markdown"
+    }
+    else if content_type.contains("x-www-form-urlencoded") {
+        "Form"
+    }
+    else if content_type.contains("json") || is_json(body) {
+        "JSON"
+    }
+    else if content_type.contains("html") || start.starts_with("<!doctype html") || start.starts_with("<html") {
+        "HTML"
+    }
+    else if content_type.contains("xml") || start.starts_with("<?xml") {
+        "XML"
+    }
+    else if body.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+        "Hex"
+    }
+    else {
+        "Text"
+    }
+}
+
+// Display-only rendering of a response body in the chosen mode ("Auto", "JSON", "XML", "HTML", "Markdown", "Form", "Text"
+// or "Hex"), Markdown and Form are previewed by the UI from the source returned here
+pub fn render_body(mode: &str, content_type: &str, view: &str, body: &str) -> String {
+    let kind = if mode == "Auto" { sniff_kind(content_type, body) } else { mode };
+    match kind {
+        "JSON" => format_body(view, body),
+        "XML" if view == "Pretty" => pretty_xml(body).unwrap_or_else(|| body.to_string()),
+        "Hex" => hex_dump(body.as_bytes()),
+        _ => body.to_string(),
+    }
+}
+
+// Classic 16 bytes per row: offset, hex bytes, printable ASCII
+pub fn hex_dump(bytes: &[u8]) -> String {
+    bytes.chunks(16).enumerate()
+        .map(|(row, chunk)| hex_row(chunk, row * 16, 16))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// One dump row, a short last row is padded so its ASCII column lines up
+pub fn hex_row(chunk: &[u8], offset: usize, bytes_per_row: usize) -> String {
+    let ascii: String = chunk.iter().map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' }).collect();
+    format!("{:08x}  {:<width$}  |{}|", offset, hex_bytes(chunk), ascii, width = bytes_per_row * 3 - 1)
+}
+
+pub fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<String>>().join(" ")
+}
+
+// Newline-delimited JSON, either declared by the content type or detected from every line parsing
+pub fn ndjson_lines(content_type: &str, body: &str) -> Option<Vec<String>> {
+    let content_type = content_type.to_ascii_lowercase();
+    let declared = content_type.contains("ndjson") || content_type.contains("jsonl") || content_type.contains("json-seq");
+    let lines: Vec<&str> = body.lines()
+        .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\u{1e}'))
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if !declared && lines.len() < 2 {
+        return None;
+    }
+
+    let mut pretty_lines = Vec::with_capacity(lines.len());
+    for line in lines {
+        match pretty_json(line) {
+            Some(pretty) => pretty_lines.push(pretty),
+            None if declared => pretty_lines.push(line.to_string()),
+            None => return None,
+        }
+    }
+    Some(pretty_lines)
+}
+
+// Cuts the body to at most `limit` bytes on a char boundary, for display only
+pub fn truncate_for_display(body: &str, limit: usize) -> &str {
+    if body.len() <= limit {
+        return body;
+    }
+    let mut end = limit;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    &body[..end]
+}
+
+// Resolves simple dotted paths such as `data.items[0].id`, with or without a leading `$`
+pub fn json_path<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    let path = path.trim().trim_start_matches('$').trim_start_matches('.');
+    let mut current = value;
+
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let (key, indexes) = match segment.find('[') {
+            Some(bracket) => segment.split_at(bracket),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for index in indexes.split('[').filter(|index| !index.is_empty()) {
+            let index: usize = index.strip_suffix(']')?.parse().ok()?;
+            current = current.get(index)?;
+        }
+    }
+
+    Some(current)
+}
+
+// json_path for changing the value in place, the path has to exist already
+pub fn json_path_mut<'a>(value: &'a mut serde_json::Value, path: &str) -> Option<&'a mut serde_json::Value> {
+    let path = path.trim().trim_start_matches('$').trim_start_matches('.');
+    let mut current = value;
+
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let (key, indexes) = match segment.find('[') {
+            Some(bracket) => segment.split_at(bracket),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get_mut(key)?;
+        }
+        for index in indexes.split('[').filter(|index| !index.is_empty()) {
+            let index: usize = index.strip_suffix(']')?.parse().ok()?;
+            current = current.get_mut(index)?;
+        }
+    }
+
+    Some(current)
+}
+
+pub fn watch_values(body: &str, watches: &[String]) -> Option<Vec<String>> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    Some(watches.iter().map(|watch| match json_path(&value, watch) {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(found) => found.to_string(),
+        None => "(missing)".to_string(),
+    }).collect())
+}
+
+pub const LONG_LINE_THRESHOLD: usize = 2000;
+
+// egui lays out very long single lines slowly, so break them up ("Wrap") or cut them short ("Collapse")
+pub fn guard_long_lines(text: String, mode: &str) -> (String, usize) {
+    let long_lines = text.lines().filter(|line| line.len() > LONG_LINE_THRESHOLD).count();
+    if long_lines == 0 || mode == "Off" {
+        return (text, long_lines);
+    }
+
+    let mut guarded = String::with_capacity(text.len() + text.len() / LONG_LINE_THRESHOLD);
+    for line in text.split_inclusive('\n') {
+        if line.len() <= LONG_LINE_THRESHOLD {
+            guarded.push_str(line);
+            continue;
+        }
+
+        let newline = if line.ends_with('\n') { "\n" } else { "" };
+        let content = line.trim_end_matches('\n');
+        if mode == "Collapse" {
+            let kept = truncate_for_display(content, LONG_LINE_THRESHOLD);
+            guarded.push_str(&format!("{}… [{} more bytes]{}", kept, content.len() - kept.len(), newline));
+            continue;
+        }
+
+        let mut rest = content;
+        while rest.len() > LONG_LINE_THRESHOLD {
+            let chunk = truncate_for_display(rest, LONG_LINE_THRESHOLD);
+            guarded.push_str(chunk);
+            guarded.push('\n');
+            rest = &rest[chunk.len()..];
+        }
+        guarded.push_str(rest);
+        guarded.push_str(newline);
+    }
+
+    (guarded, long_lines)
+}
+
+pub struct CacheInfo {
+    pub cached: bool,
+    pub summary: String,
+}
+
+// Summarises Age, Cache-Control and Expires, treating a 304 or an Age header as a cache hit
+pub fn cache_info(status: &str, headers: &[String]) -> Option<CacheInfo> {
+    let headers = headers.join("\n");
+    let not_modified = status.starts_with("304");
+    let age = header_value(&headers, "Age").and_then(|age| age.parse::<u64>().ok());
+    let cache_control = header_value(&headers, "Cache-Control");
+    let expires = header_value(&headers, "Expires");
+
+    if !not_modified && age.is_none() && cache_control.is_none() && expires.is_none() {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    if not_modified {
+        parts.push("304 Not Modified".to_string());
+    }
+    if let Some(age) = age {
+        parts.push(format!("Age {}s", age));
+    }
+    if let Some(cache_control) = &cache_control {
+        let directives: Vec<String> = cache_control.split(',').map(|d| d.trim().to_ascii_lowercase()).collect();
+        let max_age = directives.iter()
+            .find_map(|d| d.strip_prefix("s-maxage=").or_else(|| d.strip_prefix("max-age=")))
+            .and_then(|value| value.parse::<u64>().ok());
+
+        if directives.iter().any(|d| d == "no-store") {
+            parts.push("no-store".to_string());
+        }
+        else if directives.iter().any(|d| d == "no-cache") {
+            parts.push("no-cache (must revalidate)".to_string());
+        }
+        else if let Some(max_age) = max_age {
+            let remaining = max_age as i64 - age.unwrap_or(0) as i64;
+            if remaining > 0 {
+                parts.push(format!("max-age {}s, fresh for {}s", max_age, remaining));
+            }
+            else {
+                parts.push(format!("max-age {}s, stale by {}s", max_age, -remaining));
+            }
+        }
+    }
+    else if let Some(expires) = expires {
+        parts.push(format!("Expires {}", expires));
+    }
+
+    Some(CacheInfo {
+        cached: not_modified || age.is_some(),
+        summary: parts.join(", "),
+    })
+}
+
+// Validators from a previous response, ready to resend as a conditional request
+pub fn conditional_headers(request_headers: &str, response_headers: &[String]) -> Option<String> {
+    let response_headers = response_headers.join("\n");
+    let etag = header_value(&response_headers, "ETag");
+    let last_modified = header_value(&response_headers, "Last-Modified");
+    if etag.is_none() && last_modified.is_none() {
+        return None;
+    }
+
+    let mut headers: Vec<String> = request_headers.lines()
+        .filter(|line| {
+            let name = line.split(':').next().unwrap_or("").trim();
+            !name.eq_ignore_ascii_case("If-None-Match") && !name.eq_ignore_ascii_case("If-Modified-Since")
+        })
+        .map(|line| line.to_string())
+        .collect();
+    if let Some(etag) = etag {
+        headers.push(format!("If-None-Match: {}", etag));
+    }
+    if let Some(last_modified) = last_modified {
+        headers.push(format!("If-Modified-Since: {}", last_modified));
+    }
+    Some(headers.join("\n"))
+}
+
+// Differences between a stored response and a fresh one, empty when they match
+pub fn compare_responses(old_status: &str, old_body: &str, new_status: &str, new_body: &str) -> Vec<String> {
+    let mut differences = Vec::new();
+    if old_status != new_status {
+        differences.push(format!("Status: {} -> {}", old_status, new_status));
+    }
+
+    // JSON bodies are compared by value so key order and whitespace don't count
+    let same_body = match (serde_json::from_str::<serde_json::Value>(old_body), serde_json::from_str::<serde_json::Value>(new_body)) {
+        (Ok(old_value), Ok(new_value)) => old_value == new_value,
+        _ => old_body == new_body,
+    };
+    if !same_body {
+        let line = old_body.lines().zip(new_body.lines())
+            .position(|(old_line, new_line)| old_line != new_line)
+            .unwrap_or_else(|| old_body.lines().count().min(new_body.lines().count()));
+        differences.push(format!("Body differs from line {} ({} -> {} bytes)", line + 1, old_body.len(), new_body.len()));
+    }
+
+    differences
+}
+
+// What changed between the request as typed and as sent: URL rewrites and headers the
+// client added, dropped or normalized. sent_request is the request part of a raw exchange.
+pub fn request_modifications(typed_url: &str, sent_url: &str, typed_headers: &str, sent_request: &str) -> Vec<String> {
+    let mut modifications = Vec::new();
+    if !typed_url.is_empty() && typed_url != sent_url {
+        modifications.push(format!("URL: {} -> {}", typed_url, sent_url));
+    }
+    if sent_request.is_empty() {
+        return modifications;
+    }
+
+    let sent: Vec<(String, String)> = sent_request.lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let typed: Vec<(String, String)> = typed_headers.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.split_once(':').unwrap_or((line, "")))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    for (name, value) in &sent {
+        match typed.iter().find(|(typed_name, _)| typed_name.eq_ignore_ascii_case(name)) {
+            None => modifications.push(format!("Added {}: {}", name, value)),
+            // A filled-in {{name}} placeholder or a value masked for history isn't a change
+            Some((_, typed_value)) if typed_value.contains("{{") || value.contains("***") => (),
+            Some((_, typed_value)) if typed_value != value => modifications.push(format!("Changed {}: {} -> {}", name, typed_value, value)),
+            _ => (),
+        }
+    }
+    for (name, _) in &typed {
+        if !sent.iter().any(|(sent_name, _)| sent_name.eq_ignore_ascii_case(name)) {
+            modifications.push(format!("Not sent: {}", name));
+        }
+    }
+
+    modifications
+}
+
+// One server-sent event block as "event: data", None for comment-only or empty blocks
+pub fn sse_event(block: &str) -> Option<String> {
+    let mut event = "message";
+    let mut data = Vec::new();
+    for line in block.lines() {
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event = value,
+            "data" => data.push(value),
+            _ => (),
+        }
+    }
+
+    if data.is_empty() {
+        return None;
+    }
+    Some(format!("{}: {}", event, data.join("\n")))
+}
+
+// Byte ranges of case-insensitive matches, ASCII lowercasing keeps the offsets valid for the original text
+pub fn find_matches(text: &str, query: &str) -> Vec<std::ops::Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let haystack = text.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+    haystack.match_indices(&needle).map(|(start, _)| start..start + needle.len()).collect()
+}
+
+// One level of percent-decoding for display, so a double-encoded "%2520" shows up as "%20"
+pub fn percent_decode(text: &str) -> String {
+    percent_encoding::percent_decode_str(text).decode_utf8_lossy().to_string()
+}
+
+// Form pairs exactly as typed, for comparing against parse_form's decoded ones
+pub fn split_form(body: &str) -> Vec<(String, String)> {
+    body.trim().split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect()
+}
+
+// "Name: value" lines as a JSON object, a repeated name becomes an array of its values
+pub fn headers_json(headers: &str) -> String {
+    let mut object = serde_json::Map::new();
+    for (name, value) in headers.lines().filter_map(|line| line.split_once(':')) {
+        let value = serde_json::Value::String(value.trim().to_string());
+        match object.get_mut(name.trim()) {
+            Some(serde_json::Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = serde_json::Value::Array(vec![existing.take(), value]),
+            None => {
+                object.insert(name.trim().to_string(), value);
+            },
+        }
+    }
+    serde_json::to_string_pretty(&object).unwrap_or_default()
+}
+
+// Request headers whose values are a comma-separated list (RFC 9110 5.3), so "Accept: a, b" means the
+// same as two Accept lines. Anything else, like Cookie, Authorization or Date, is sent as one value
+pub const COMMA_COMBINABLE_HEADERS: [&str; 14] = [
+    "Accept", "Accept-Encoding", "Accept-Language", "Accept-Charset", "Cache-Control", "Connection", "Forwarded",
+    "If-Match", "If-None-Match", "Pragma", "TE", "Upgrade", "Via", "X-Forwarded-For",
+];
+
+pub fn comma_combinable(name: &str) -> bool {
+    COMMA_COMBINABLE_HEADERS.iter().any(|header| header.eq_ignore_ascii_case(name.trim()))
+}
+
+// The reverse of headers_json, for pasting several headers at once as {"Name": "value", ...}. An array
+// is one comma-separated line for a list header and one line per value otherwise
+pub fn headers_from_json(text: &str) -> Option<String> {
+    let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(text.trim()).ok()?;
+    let value_text = |value: &serde_json::Value| match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    let mut lines = Vec::new();
+    for (name, value) in &object {
+        match value {
+            serde_json::Value::Array(values) if comma_combinable(name) => {
+                lines.push(format!("{}: {}", name, values.iter().map(value_text).collect::<Vec<_>>().join(", ")));
+            },
+            serde_json::Value::Array(values) => lines.extend(values.iter().map(|value| format!("{}: {}", name, value_text(value)))),
+            value => lines.push(format!("{}: {}", name, value_text(value))),
+        }
+    }
+    Some(lines.join("\n"))
+}
+
+pub const ENCODINGS: [&str; 6] = ["URL encode", "URL decode", "Base64 encode", "Base64 decode", "JSON escape", "JSON unescape"];
+
+// Unreserved characters stay as they are, everything else is percent-encoded as a URL component
+const URL_COMPONENT: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+pub fn encode(operation: &str, input: &str) -> Result<String, String> {
+    match operation {
+        "URL encode" => Ok(percent_encoding::utf8_percent_encode(input, URL_COMPONENT).to_string()),
+        "URL decode" => percent_encoding::percent_decode_str(input).decode_utf8()
+            .map(|decoded| decoded.to_string())
+            .map_err(|e| format!("Decoded bytes aren't UTF-8: {}", e)),
+        "Base64 encode" => Ok(to_base64(input)),
+        "Base64 decode" => {
+            let bytes = base64::engine::general_purpose::STANDARD.decode(input.trim()).map_err(|e| format!("Invalid base64: {}", e))?;
+            String::from_utf8(bytes).map_err(|e| format!("Decoded {} bytes aren't UTF-8 text", e.as_bytes().len()))
+        },
+        // The quotes serde_json adds are dropped so the result pastes into an existing string
+        "JSON escape" => serde_json::to_string(input)
+            .map(|escaped| escaped[1..escaped.len() - 1].to_string())
+            .map_err(|e| e.to_string()),
+        "JSON unescape" => {
+            let quoted = if input.len() > 1 && input.starts_with('"') && input.ends_with('"') { input.to_string() } else { format!("\"{}\"", input) };
+            serde_json::from_str::<String>(&quoted).map_err(|e| format!("Invalid JSON string: {}", e))
+        },
+        _ => Err(format!("Unknown operation: {}", operation)),
+    }
+}
+
+// Basic JSON Schema for a response: types, properties and the keys every object had
+pub fn infer_schema(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    // A bare number or string body isn't a shape worth describing
+    if !value.is_object() && !value.is_array() {
+        return None;
+    }
+    let mut schema = schema_of(&value);
+    if let serde_json::Value::Object(object) = &mut schema {
+        object.insert("$schema".to_string(), "https://json-schema.org/draft/2020-12/schema".into());
+    }
+    serde_json::to_string_pretty(&schema).ok()
+}
+
+fn schema_of(value: &serde_json::Value) -> serde_json::Value {
+    use serde_json::{json, Value};
+    match value {
+        Value::Null => json!({ "type": "null" }),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(number) if number.is_f64() => json!({ "type": "number" }),
+        Value::Number(_) => json!({ "type": "integer" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Array(items) => match items.iter().map(schema_of).reduce(merge_schemas) {
+            Some(item_schema) => json!({ "type": "array", "items": item_schema }),
+            None => json!({ "type": "array" }),
+        },
+        Value::Object(map) => json!({
+            "type": "object",
+            "properties": map.iter().map(|(key, child)| (key.clone(), schema_of(child))).collect::<serde_json::Map<String, Value>>(),
+            "required": map.keys().collect::<Vec<_>>(),
+        }),
+    }
+}
+
+// Array items may differ: objects merge their properties and keep only the keys all of them had,
+// integer widens to number, anything else becomes anyOf
+fn merge_schemas(a: serde_json::Value, b: serde_json::Value) -> serde_json::Value {
+    use serde_json::{json, Value};
+    if a == b {
+        return a;
+    }
+    match (a["type"].as_str(), b["type"].as_str()) {
+        (Some("object"), Some("object")) => {
+            let mut properties = a["properties"].as_object().cloned().unwrap_or_default();
+            for (key, schema) in b["properties"].as_object().cloned().unwrap_or_default() {
+                let merged = match properties.remove(&key) {
+                    Some(existing) => merge_schemas(existing, schema),
+                    None => schema,
+                };
+                properties.insert(key, merged);
+            }
+            let required_b = b["required"].as_array().cloned().unwrap_or_default();
+            let required: Vec<Value> = a["required"].as_array().cloned().unwrap_or_default().into_iter().filter(|key| required_b.contains(key)).collect();
+            json!({ "type": "object", "properties": properties, "required": required })
+        },
+        (Some("array"), Some("array")) => match (a.get("items"), b.get("items")) {
+            (Some(items_a), Some(items_b)) => json!({ "type": "array", "items": merge_schemas(items_a.clone(), items_b.clone()) }),
+            (Some(_), None) => a,
+            _ => b,
+        },
+        (Some("integer"), Some("number")) | (Some("number"), Some("integer")) => json!({ "type": "number" }),
+        _ => {
+            let mut any_of = a.get("anyOf").and_then(|any_of| any_of.as_array()).cloned().unwrap_or_else(|| vec![a.clone()]);
+            if !any_of.contains(&b) {
+                any_of.push(b);
+            }
+            json!({ "anyOf": any_of })
+        },
+    }
+}
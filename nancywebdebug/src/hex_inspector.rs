@@ -0,0 +1,83 @@
+use eframe::egui;
+use nancywebdebug::format;
+
+pub const DEFAULT_BYTES_PER_ROW: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct HexInspector {
+    pub bytes_per_row: usize,
+    goto: usize,
+    // Row to scroll to on the next frame and the byte it was for
+    scroll_to: Option<usize>,
+    highlight: Option<usize>,
+    copy_from: usize,
+    copy_to: usize,
+    // Response index and its bytes, decoded once per selection
+    cache: Option<(usize, Vec<u8>)>,
+}
+
+impl HexInspector {
+    pub fn new(bytes_per_row: usize) -> Self {
+        HexInspector {
+            bytes_per_row,
+            goto: 0,
+            scroll_to: None,
+            highlight: None,
+            copy_from: 0,
+            copy_to: 0,
+            cache: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, index: usize, bytes: impl FnOnce() -> Vec<u8>) {
+        if self.cache.as_ref().is_none_or(|(cached, _)| *cached != index) {
+            self.cache = Some((index, bytes()));
+            self.highlight = None;
+        }
+        let Some((_, bytes)) = &self.cache else { return };
+        let last = bytes.len().saturating_sub(1);
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} bytes", bytes.len()));
+            ui.add_space(10.0);
+            ui.label("Bytes per row:");
+            ui.add(egui::DragValue::new(&mut self.bytes_per_row).range(4..=64));
+            ui.add_space(10.0);
+            ui.label("Offset:");
+            ui.add(egui::DragValue::new(&mut self.goto).range(0..=last).hexadecimal(1, false, true).prefix("0x"));
+            if ui.button("Go").clicked() {
+                self.scroll_to = Some(self.goto / self.bytes_per_row);
+                self.highlight = Some(self.goto);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Copy from:");
+            ui.add(egui::DragValue::new(&mut self.copy_from).range(0..=bytes.len()).hexadecimal(1, false, true).prefix("0x"));
+            ui.label("to:");
+            self.copy_to = self.copy_to.clamp(self.copy_from, bytes.len());
+            ui.add(egui::DragValue::new(&mut self.copy_to).range(self.copy_from..=bytes.len()).hexadecimal(1, false, true).prefix("0x"))
+                .on_hover_text("End offset is exclusive");
+            if ui.add_enabled(self.copy_to > self.copy_from, egui::Button::new("Copy hex")).clicked() {
+                ui.ctx().copy_text(format::hex_bytes(&bytes[self.copy_from..self.copy_to]));
+            }
+        });
+
+        // Only the visible rows are formatted, so multi-megabyte bodies stay responsive
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let mut area = egui::ScrollArea::both().id_salt("hex_inspector_scroll").max_height(250.0);
+        if let Some(row) = self.scroll_to.take() {
+            area = area.vertical_scroll_offset(row as f32 * (row_height + ui.spacing().item_spacing.y));
+        }
+        area.show_rows(ui, row_height, bytes.len().div_ceil(self.bytes_per_row), |ui, rows| {
+            for row in rows {
+                let start = row * self.bytes_per_row;
+                let chunk = &bytes[start..(start + self.bytes_per_row).min(bytes.len())];
+                let mut text = egui::RichText::new(format::hex_row(chunk, start, self.bytes_per_row)).monospace();
+                if self.highlight.is_some_and(|offset| (start..start + chunk.len()).contains(&offset)) {
+                    text = text.background_color(ui.visuals().selection.bg_fill);
+                }
+                ui.add(egui::Label::new(text).extend());
+            }
+        });
+    }
+}
@@ -0,0 +1,148 @@
+use eframe::egui;
+use serde_json::Value;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default)]
+pub struct TreeSearch {
+    query: String,
+    exact_key: bool,
+    // Response index, query and mode the matches below were found with
+    searched: Option<(usize, String, bool)>,
+    // Matching node paths and the containers above them
+    hits: HashSet<String>,
+    open: HashSet<String>,
+    // Set when the matches change, so containers holding one are opened once and can still be collapsed
+    reveal: bool,
+}
+
+impl TreeSearch {
+    // Search box and match count, matches are only looked for again when something changes
+    pub fn bar(&mut self, ui: &mut egui::Ui, index: usize, value: &Value) {
+        ui.horizontal(|ui| {
+            ui.label("Search tree:");
+            ui.add(egui::TextEdit::singleline(&mut self.query).desired_width(180.0).hint_text("key or value"));
+            ui.checkbox(&mut self.exact_key, "Exact key")
+                .on_hover_text("Match whole key names only, instead of any key or value containing the text");
+
+            let key = (index, self.query.clone(), self.exact_key);
+            if self.searched.as_ref() != Some(&key) {
+                self.hits.clear();
+                self.open.clear();
+                if !self.query.is_empty() {
+                    find(value, "$", "$".to_string(), &self.query.to_lowercase(), self.exact_key, &mut self.hits, &mut self.open);
+                }
+                self.searched = Some(key);
+                self.reveal = !self.hits.is_empty();
+            }
+            if !self.query.is_empty() {
+                ui.label(format!("{} match(es)", self.hits.len()));
+            }
+        });
+    }
+}
+
+// Fills hits with the matching paths and open with every container holding one, returns whether this node did
+fn find(value: &Value, key: &str, path: String, query: &str, exact_key: bool, hits: &mut HashSet<String>, open: &mut HashSet<String>) -> bool {
+    let mut found = false;
+    match value {
+        Value::Object(map) => {
+            for (child_key, child) in map {
+                found |= find(child, child_key, format!("{}.{}", path, child_key), query, exact_key, hits, open);
+            }
+        },
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                found |= find(child, &index.to_string(), format!("{}[{}]", path, index), query, exact_key, hits, open);
+            }
+        },
+        _ => (),
+    }
+    if found {
+        open.insert(path.clone());
+    }
+
+    let matched = if exact_key {
+        key.to_lowercase() == query
+    }
+    else {
+        key.to_lowercase().contains(query) || (!value.is_object() && !value.is_array() && value.to_string().to_lowercase().contains(query))
+    };
+    if matched {
+        hits.insert(path);
+    }
+    found || matched
+}
+
+pub fn show(ui: &mut egui::Ui, value: &Value, search: &mut TreeSearch) {
+    let matches = (!search.query.is_empty()).then_some((&search.hits, &search.open, search.reveal));
+    show_node(ui, "$", value, "$".to_string(), true, matches);
+    search.reveal = false;
+}
+
+fn show_node(ui: &mut egui::Ui, key: &str, value: &Value, path: String, default_open: bool, matches: Option<(&HashSet<String>, &HashSet<String>, bool)>) {
+    // Containers with a match inside are opened when the search changes
+    let open = matches.map(|(_, open, reveal)| reveal && open.contains(&path)).filter(|open| *open);
+    let hit = matches.is_some_and(|(hits, ..)| hits.contains(&path));
+    let fill = ui.visuals().selection.bg_fill;
+    let highlight = |text: egui::RichText| if hit { text.background_color(fill) } else { text };
+    match value {
+        Value::Object(map) => {
+            let header = egui::CollapsingHeader::new(highlight(egui::RichText::new(format!("{} {{{}}}", key, map.len()))))
+                .id_salt(&path)
+                .default_open(default_open)
+                .open(open)
+                .show(ui, |ui| {
+                    for (child_key, child) in map {
+                        show_node(ui, child_key, child, format!("{}.{}", path, child_key), false, matches);
+                    }
+                });
+            copy_menu(&header.header_response, value, &path);
+        },
+        Value::Array(items) => {
+            let header = egui::CollapsingHeader::new(highlight(egui::RichText::new(format!("{} [{}]", key, items.len()))))
+                .id_salt(&path)
+                .default_open(default_open)
+                .open(open)
+                .show(ui, |ui| {
+                    for (index, child) in items.iter().enumerate() {
+                        show_node(ui, &index.to_string(), child, format!("{}[{}]", path, index), false, matches);
+                    }
+                });
+            copy_menu(&header.header_response, value, &path);
+        },
+        _ => {
+            let response = ui.horizontal(|ui| {
+                ui.label(highlight(egui::RichText::new(format!("{}:", key)).strong()));
+                ui.label(highlight(egui::RichText::new(value.to_string()).color(scalar_color(value))));
+            }).response;
+            copy_menu(&response, value, &path);
+        },
+    }
+}
+
+// Right-click any node to copy it or its path
+fn copy_menu(response: &egui::Response, value: &Value, path: &str) {
+    response.context_menu(|ui| {
+        if ui.button("Copy value").clicked() {
+            let text = match value {
+                Value::String(text) => text.clone(),
+                _ => serde_json::to_string_pretty(value).unwrap_or_default(),
+            };
+            ui.ctx().copy_text(text);
+            ui.close_menu();
+        }
+        if ui.button("Copy path").clicked() {
+            ui.ctx().copy_text(path.to_string());
+            ui.close_menu();
+        }
+    });
+}
+
+fn scalar_color(value: &Value) -> egui::Color32 {
+    match value {
+        Value::String(_) => egui::Color32::from_rgb(110, 180, 110),
+        Value::Number(_) => egui::Color32::from_rgb(110, 160, 220),
+        Value::Bool(_) => egui::Color32::from_rgb(220, 150, 80),
+        _ => egui::Color32::GRAY,
+    }
+}
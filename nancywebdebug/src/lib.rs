@@ -0,0 +1,4 @@
+pub mod auth;
+pub mod format;
+mod pool;
+pub mod request;
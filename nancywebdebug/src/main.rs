@@ -1,24 +1,235 @@
-mod request;
+mod assertion;
+mod config_files;
+mod default_headers;
+mod environment;
+mod hex_inspector;
+mod json_tree;
+mod markdown;
+mod oauth;
+mod saved;
+mod ui_settings;
 
+use base64::Engine;
 use eframe::egui;
+use nancywebdebug::{auth, format, request};
+use std::collections::HashMap;
 use std::error::Error;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
 
-#[derive(Debug, Clone)]
+const APP_NAME: &str = "Nancy Web Debugger";
+// Hand-editable config in the storage dir, see config_files
+const ENVIRONMENTS_FILE: &str = "environments.json";
+const SAVED_REQUESTS_FILE: &str = "saved_requests.json";
+const DEFAULT_HEADERS_FILE: &str = "default_headers.json";
+const DEFAULT_TEXT_SIZE: f32 = 12.5;
+const MIN_TEXT_SIZE: f32 = 8.0;
+const MAX_TEXT_SIZE: f32 = 32.0;
+const DEFAULT_BODY_DISPLAY_KB: usize = 256;
+const DEFAULT_REPLAY_CONCURRENCY: usize = 4;
+const DEFAULT_CACHE_TTL_SECS: u64 = 60;
+const DEFAULT_CACHE_ENTRIES: usize = 50;
+// First-run size of the New Request window, egui remembers it once resized
+const NEW_REQUEST_SIZE: [f32; 2] = [420.0, 640.0];
+// Typed bodies past this get a hint to stream from a file instead
+const LARGE_BODY_BYTES: usize = 5 * 1024 * 1024;
+const HISTORY_GROUPINGS: [&str; 3] = ["Flat", "Host", "Host + path"];
+const HISTORY_DENSITIES: [&str; 2] = ["Detailed", "Compact"];
+// Compact history rows cut the URL here, the full one is on hover
+const COMPACT_URL_BYTES: usize = 80;
+// Quick Accept buttons under the headers box
+const ACCEPT_PRESETS: [&str; 4] = ["application/json", "text/html", "*/*", "application/xml"];
+// Method combo entries in order, Ctrl+M and the arrow keys step through them
+const METHODS: [&str; 6] = ["GET", "POST", "PUT", "PATCH", "DELETE", "Custom..."];
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct RequestResult {
     index: usize,
 
     // Request
+    #[serde(default = "default_method")]
+    method: String,
     req_headers: String,
     req_body: String,
 
     // Response
     url: String,
+    #[serde(default)]
+    scheme: String,
+    // No scheme was typed, so normalize_url fell back to http://
+    #[serde(default)]
+    auto_http: bool,
     status: String,
     headers: Vec<String>,
     body: String,
+    trace: String,
     error: Option<String>,
+    // Request and response head as they were on the wire, without the response body
+    #[serde(default)]
+    raw_exchange: String,
+
+    #[serde(default)]
+    note: String,
+
+    // Per-request proxy, reused on resend
+    #[serde(default)]
+    proxy: String,
+
+    #[serde(default)]
+    duration_ms: u64,
+
+    // Approximate bytes of the request line, headers and body as sent
+    #[serde(default)]
+    request_size: usize,
+
+    // The response bytes when `body` isn't an exact copy of them (binary, non-UTF-8 charsets)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    body_base64: String,
+
+    // Checked against this entry's response, resends carry them over
+    #[serde(default)]
+    assertions: Vec<assertion::Assertion>,
+
+    // URL before normalize_url, for showing what was rewritten
+    #[serde(default)]
+    typed_url: String,
+
+    // Set when the URL was a path joined onto an environment's base URL
+    #[serde(default)]
+    template: Option<RequestTemplate>,
+
+    // Client tier the fallback started at, empty for Standard
+    #[serde(default)]
+    first_client: String,
+
+    // The body was left out because the method usually has none, req_body still holds what was typed
+    #[serde(default)]
+    drop_bodyless_body: bool,
+
+    // File the body was streamed from, resends read it again
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    body_file: String,
+
+    // Sent after a chunked body, kept apart from the headers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trailers: Vec<String>,
+
+    // Each redirect response on the way, when redirect bodies were kept
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    redirects: Vec<request::RedirectHop>,
+
+    // Environment the URL was resolved against, or else the one active when it was sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    environment: Option<String>,
+
+    // Answered from the response cache instead of being sent
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    from_cache: bool,
+
+    // The response started but the connection broke while reading the body, which holds what arrived
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    body_read_failed: bool,
+
+    // Default header sets merged in when it was sent, by host. req_headers only holds what was typed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    default_header_sets: Vec<String>,
+}
+
+// Request signature, when its response was stored and the history index holding it
+type CachedResponses = Vec<(String, std::time::Instant, usize)>;
+
+// Find matches in the shown body by response, query and the body's length. The length changes when
+// the body view or truncation swaps the text without another response being selected
+type FindCache = (Option<usize>, String, usize, Vec<std::ops::Range<usize>>);
+
+impl RequestResult {
+    fn body_bytes(&self) -> Vec<u8> {
+        if self.body_base64.is_empty() {
+            return self.body.as_bytes().to_vec();
+        }
+        base64::engine::general_purpose::STANDARD.decode(&self.body_base64).unwrap_or_else(|_| self.body.as_bytes().to_vec())
+    }
+
+    // The request rebuilt in HTTP/1.1 form from the head in the raw exchange, which has secrets
+    // masked, and the body. Nothing extra is saved for it, the body is already in req_body
+    fn sent_bytes(&self) -> Vec<u8> {
+        let head = self.raw_exchange.split("\n\n").next().unwrap_or("");
+        let mut bytes = head.replace('\n', "\r\n").into_bytes();
+        bytes.extend_from_slice(b"\r\n\r\n");
+        if self.body_file.is_empty() && !(self.drop_bodyless_body && request::usually_bodyless(&self.method)) {
+            bytes.extend_from_slice(self.req_body.as_bytes());
+        }
+        bytes
+    }
+
+    fn carry_over(&self) -> CarryOver {
+        CarryOver {
+            assertions: self.assertions.clone(),
+            template: self.template.clone(),
+            first_client: self.first_client.clone(),
+            drop_bodyless_body: self.drop_bodyless_body,
+            body_file: self.body_file.clone(),
+            refresh_cache: false,
+        }
+    }
+}
+
+// The URL field as typed, before the active environment's base URL was joined on
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct RequestTemplate {
+    environment: String,
+    url: String,
+}
+
+// What a resend copies from the entry it came from, on top of method, URL, headers and body
+#[derive(Debug, Clone, Default)]
+struct CarryOver {
+    assertions: Vec<assertion::Assertion>,
+    template: Option<RequestTemplate>,
+    first_client: String,
+    drop_bodyless_body: bool,
+    body_file: String,
+    // Send even when the response cache has an answer
+    refresh_cache: bool,
+}
+
+// Request side of a history entry, for pasting between users of this tool
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct SharedRequest {
+    method: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    headers: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    body: String,
+}
+
+// One replayed history entry, differences is empty when the response still matches
+#[derive(Debug, Clone)]
+struct ReplayOutcome {
+    method: String,
+    url: String,
+    differences: Vec<String>,
+}
+
+// Clears a busy flag when the worker that owns it exits, however it exits
+struct BusyGuard(Arc<Mutex<bool>>);
+
+impl BusyGuard {
+    fn set(flag: &Arc<Mutex<bool>>) -> Self {
+        *flag.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        BusyGuard(Arc::clone(flag))
+    }
+}
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = false;
+    }
+}
+
+fn default_method() -> String {
+    "GET".to_string()
 }
 
 #[derive(Debug, Clone)]
@@ -28,44 +239,323 @@ struct App {
     show_requestheaders: Arc<Mutex<String>>,
     show_responsedetails: Arc<Mutex<String>>,
     show_responseheaders: Arc<Mutex<String>>,
-    selected_response_index: Option<usize>,
+    show_diagnostics: Arc<Mutex<String>>,
+    selected_response_index: Arc<Mutex<Option<usize>>>,
+    body_view: Arc<Mutex<String>>,
+    render_mode: Arc<Mutex<String>>,
+    body_display_kb: Arc<Mutex<usize>>,
+    show_full_body: Arc<Mutex<bool>>,
+    long_lines: Arc<Mutex<usize>>,
     set_focus: String,
+    show_body_preview: bool,
+    format_on_paste: bool,
+    // Appends one JSON line per request to log_path while enabled
+    log_enabled: bool,
+    log_path: String,
+    // Method the body-on-GET warning was dismissed for
+    body_warning_dismissed: Option<String>,
+    // Off leaves the body out of GET, DELETE and the other usually bodyless methods
+    allow_bodyless_body: bool,
+    // Follow redirects one at a time and keep each 3xx response with the entry
+    capture_redirects: bool,
+    // Path to stream the body from, empty sends the typed body
+    body_file: String,
+    upload_progress: request::UploadProgress,
+    import_json: String,
+    import_raw: String,
+    import_raw_error: Option<String>,
+    // Copy of an entry being resent with one-off header/body changes, the entry itself is untouched
+    resend_override: Option<RequestResult>,
+    import_error: Option<String>,
+    ndjson_raw: bool,
+    // Shows the text of a Markdown or form body instead of its preview
+    preview_source: bool,
+    ndjson_cache: Option<(usize, Option<Vec<String>>)>,
+    json_tree_cache: Option<(usize, Option<serde_json::Value>)>,
+    tree_search: json_tree::TreeSearch,
+    schema_cache: Option<(usize, Option<String>)>,
+    sniff_cache: Option<(usize, &'static str)>,
+    watches: Vec<String>,
+    history_order: String,
+    // One of HISTORY_GROUPINGS
+    history_grouping: String,
+    history_autoscroll: bool,
+    // Newest entry already scrolled to, a higher index means a response just arrived
+    history_newest_seen: Option<usize>,
+    new_watch: String,
+    new_assertion_kind: String,
+    new_assertion_value: String,
+    watch_cache: HashMap<usize, Option<Vec<String>>>,
+    ui_settings: ui_settings::UiSettings,
+    trace_line_numbers: bool,
+    // Display only, the encoded URL and body are what gets sent
+    decode_display: bool,
+    // Request part of the details panel folded down to its heading
+    request_collapsed: bool,
+    hex_inspector: hex_inspector::HexInspector,
+    request_hex_inspector: hex_inspector::HexInspector,
+    find_open: bool,
+    find_query: String,
+    find_current: usize,
+    find_cache: Option<FindCache>,
+    // Set when the current match changes so the body scrolls to it once
+    find_scroll: bool,
 
     request_type: Arc<Mutex<String>>,
+    custom_method: Arc<Mutex<String>>,
     request_url: Arc<Mutex<String>>,
     request_headers: Arc<Mutex<String>>,
     request_body: Arc<Mutex<String>>,
+    request_proxy: Arc<Mutex<String>>,
+    auth_type: Arc<Mutex<String>>,
+    auth_username: Arc<Mutex<String>>,
+    auth_password: Arc<Mutex<String>>,
+    auth_token: Arc<Mutex<String>>,
+    dns_provider: Arc<Mutex<String>>,
+    exact_headers: Arc<Mutex<bool>>,
+    pool_max_idle: Arc<Mutex<usize>>,
+    raw_chunk_framing: Arc<Mutex<bool>>,
+    probe_retries: Arc<Mutex<u32>>,
+    probe_retry_delay_ms: Arc<Mutex<u64>>,
+    skip_probe: Arc<Mutex<bool>>,
+    max_response_mb: Arc<Mutex<u64>>,
+    // Network conditions, not saved so a restart never leaves requests mysteriously slow
+    send_delay_ms: Arc<Mutex<u64>>,
+    throttle_bytes_per_sec: Arc<Mutex<u64>>,
+    root_ca_file: String,
+    // Plain HTTP is refused and invalid certificates are never accepted
+    https_only: bool,
+    // One of request::DEFAULT_SCHEMES, for URLs typed without a scheme
+    default_scheme: String,
+    // Opt-in, an identical request within the TTL is answered from history without sending
+    cache_enabled: bool,
+    cache_ttl_secs: u64,
+    cache_max_entries: usize,
+    response_cache: Arc<Mutex<CachedResponses>>,
+    // The path last checked and its certificate count, so the file isn't parsed every frame
+    root_ca_check: Option<(String, Result<usize, String>)>,
+    tls_min: Arc<Mutex<String>>,
+    tls_max: Arc<Mutex<String>>,
+    first_client: Arc<Mutex<String>>,
     request_responses: Arc<Mutex<Vec<RequestResult>>>,
     request_loading: Arc<Mutex<bool>>,
     next_index: Arc<Mutex<usize>>,
+    // next_index when the app started, everything from here on is this session
+    session_start_index: usize,
+
+    environments: Vec<environment::Environment>,
+    active_environment: String,
+    show_environments: bool,
+    oauth: oauth::OAuthHelper,
+    show_oauth: bool,
+    show_encoder: bool,
+    saved_requests: Vec<saved::SavedRequest>,
+    show_saved: bool,
+    default_headers: Vec<default_headers::HeaderSet>,
+    show_default_headers: bool,
+    // Config files that failed to load, left untouched on save until fixed and reloaded
+    broken_config_files: Vec<&'static str>,
+    // History index whose request headers and body are editable in the details panel
+    editing_request: Option<usize>,
+    encoder_operation: String,
+    encoder_input: String,
+
+    show_replay: bool,
+    replay_parallel: bool,
+    replay_concurrency: usize,
+    replay_running: Arc<Mutex<bool>>,
+    replay_total: usize,
+    replay_results: Arc<Mutex<Vec<ReplayOutcome>>>,
+
+    event_stream: request::EventStream,
 
     ui_error: Option<String>,
+    // Errors from worker threads, moved into ui_error on the next frame
+    task_error: Arc<Mutex<Option<String>>>,
 }
 
 impl App {
-    fn new() -> Self {
-        App {
+    fn new(storage: Option<&dyn eframe::Storage>) -> Self {
+        let saved_responses: Vec<RequestResult> = storage
+            .and_then(|storage| eframe::get_value(storage, "request_responses"))
+            .unwrap_or_default();
+        let next_index = saved_responses.iter().map(|r| r.index).max().unwrap_or(0) + 1;
+        let load_string = |key: &str, default: &str| -> String {
+            storage.and_then(|storage| eframe::get_value(storage, key)).unwrap_or_else(|| default.to_string())
+        };
+
+        let mut app = App {
             show_newrequest: false,
             show_requestdetails: Arc::new(Mutex::new(String::new())),
             show_requestheaders: Arc::new(Mutex::new(String::new())),
             show_responsedetails: Arc::new(Mutex::new(String::new())),
             show_responseheaders: Arc::new(Mutex::new(String::new())),
-            selected_response_index: None,
+            show_diagnostics: Arc::new(Mutex::new(String::new())),
+            selected_response_index: Arc::new(Mutex::new(None)),
+            body_view: Arc::new(Mutex::new("Raw".to_string())),
+            render_mode: Arc::new(Mutex::new("Auto".to_string())),
+            body_display_kb: Arc::new(Mutex::new(storage.and_then(|storage| eframe::get_value(storage, "body_display_kb")).unwrap_or(DEFAULT_BODY_DISPLAY_KB))),
+            show_full_body: Arc::new(Mutex::new(false)),
+            long_lines: Arc::new(Mutex::new(0)),
             set_focus: String::new(),
+            show_body_preview: false,
+            format_on_paste: storage.and_then(|storage| eframe::get_value(storage, "format_on_paste")).unwrap_or(true),
+            log_enabled: storage.and_then(|storage| eframe::get_value(storage, "log_enabled")).unwrap_or(false),
+            log_path: load_string("log_path", "nancywebdebug-log.jsonl"),
+            body_warning_dismissed: None,
+            allow_bodyless_body: storage.and_then(|storage| eframe::get_value(storage, "allow_bodyless_body")).unwrap_or(true),
+            capture_redirects: storage.and_then(|storage| eframe::get_value(storage, "capture_redirects")).unwrap_or(false),
+            body_file: load_string("draft_body_file", ""),
+            upload_progress: request::UploadProgress::default(),
+            import_json: String::new(),
+            import_raw: String::new(),
+            import_raw_error: None,
+            resend_override: None,
+            import_error: None,
+            ndjson_raw: false,
+            preview_source: false,
+            ndjson_cache: None,
+            json_tree_cache: None,
+            tree_search: json_tree::TreeSearch::default(),
+            schema_cache: None,
+            sniff_cache: None,
+            watches: storage.and_then(|storage| eframe::get_value(storage, "watches")).unwrap_or_default(),
+            history_order: load_string("history_order", "Newest first"),
+            history_grouping: load_string("history_grouping", HISTORY_GROUPINGS[0]),
+            history_autoscroll: storage.and_then(|storage| eframe::get_value(storage, "history_autoscroll")).unwrap_or(true),
+            history_newest_seen: saved_responses.iter().map(|r| r.index).max(),
+            new_watch: String::new(),
+            new_assertion_kind: assertion::KINDS[0].to_string(),
+            new_assertion_value: String::new(),
+            watch_cache: HashMap::new(),
+            ui_settings: ui_settings::UiSettings::load(storage),
+            trace_line_numbers: storage.and_then(|storage| eframe::get_value(storage, "trace_line_numbers")).unwrap_or(false),
+            decode_display: storage.and_then(|storage| eframe::get_value(storage, "decode_display")).unwrap_or(true),
+            request_collapsed: storage.and_then(|storage| eframe::get_value(storage, "request_collapsed")).unwrap_or(false),
+            hex_inspector: hex_inspector::HexInspector::new(storage.and_then(|storage| eframe::get_value(storage, "hex_bytes_per_row")).unwrap_or(hex_inspector::DEFAULT_BYTES_PER_ROW)),
+            request_hex_inspector: hex_inspector::HexInspector::new(storage.and_then(|storage| eframe::get_value(storage, "hex_bytes_per_row")).unwrap_or(hex_inspector::DEFAULT_BYTES_PER_ROW)),
+            find_open: false,
+            find_query: String::new(),
+            find_current: 0,
+            find_cache: None,
+            find_scroll: false,
 
-            request_type: Arc::new(Mutex::new("GET".to_string())),
-            request_url: Arc::new(Mutex::new(String::new())),
-            request_headers: Arc::new(Mutex::new(String::new())),
-            request_body: Arc::new(Mutex::new(String::new())),
-            request_responses: Arc::new(Mutex::new(Vec::new())),
+            request_type: Arc::new(Mutex::new(load_string("draft_request_type", "GET"))),
+            custom_method: Arc::new(Mutex::new(load_string("draft_custom_method", ""))),
+            request_url: Arc::new(Mutex::new(load_string("draft_request_url", ""))),
+            request_headers: Arc::new(Mutex::new(load_string("draft_request_headers", ""))),
+            request_body: Arc::new(Mutex::new(load_string("draft_request_body", ""))),
+            request_proxy: Arc::new(Mutex::new(load_string("draft_request_proxy", ""))),
+            auth_type: Arc::new(Mutex::new("None".to_string())),
+            auth_username: Arc::new(Mutex::new(String::new())),
+            auth_password: Arc::new(Mutex::new(String::new())),
+            auth_token: Arc::new(Mutex::new(String::new())),
+            dns_provider: Arc::new(Mutex::new("System".to_string())),
+            exact_headers: Arc::new(Mutex::new(false)),
+            raw_chunk_framing: Arc::new(Mutex::new(false)),
+            probe_retries: Arc::new(Mutex::new(storage.and_then(|storage| eframe::get_value(storage, "probe_retries")).unwrap_or(request::DEFAULT_PROBE_RETRIES))),
+            probe_retry_delay_ms: Arc::new(Mutex::new(storage.and_then(|storage| eframe::get_value(storage, "probe_retry_delay_ms")).unwrap_or(request::DEFAULT_PROBE_RETRY_DELAY_MS))),
+            skip_probe: Arc::new(Mutex::new(storage.and_then(|storage| eframe::get_value(storage, "skip_probe")).unwrap_or(false))),
+            max_response_mb: Arc::new(Mutex::new(storage.and_then(|storage| eframe::get_value(storage, "max_response_mb")).unwrap_or(request::DEFAULT_MAX_RESPONSE_MB))),
+            send_delay_ms: Arc::new(Mutex::new(0)),
+            throttle_bytes_per_sec: Arc::new(Mutex::new(0)),
+            root_ca_file: load_string("root_ca_file", ""),
+            https_only: storage.and_then(|storage| eframe::get_value(storage, "https_only")).unwrap_or(false),
+            default_scheme: load_string("default_scheme", request::DEFAULT_SCHEMES[0]),
+            cache_enabled: storage.and_then(|storage| eframe::get_value(storage, "cache_enabled")).unwrap_or(false),
+            cache_ttl_secs: storage.and_then(|storage| eframe::get_value(storage, "cache_ttl_secs")).unwrap_or(DEFAULT_CACHE_TTL_SECS),
+            cache_max_entries: storage.and_then(|storage| eframe::get_value(storage, "cache_max_entries")).unwrap_or(DEFAULT_CACHE_ENTRIES),
+            response_cache: Arc::new(Mutex::new(Vec::new())),
+            root_ca_check: None,
+            tls_min: Arc::new(Mutex::new("Any".to_string())),
+            tls_max: Arc::new(Mutex::new("Any".to_string())),
+            first_client: Arc::new(Mutex::new(request::CLIENT_TIERS[0].to_string())),
+            pool_max_idle: Arc::new(Mutex::new(storage.and_then(|storage| eframe::get_value(storage, "pool_max_idle")).unwrap_or(request::DEFAULT_POOL_MAX_IDLE))),
+            request_responses: Arc::new(Mutex::new(saved_responses)),
             request_loading: Arc::new(Mutex::new(false)),
-            next_index: Arc::new(Mutex::new(1)),
+            next_index: Arc::new(Mutex::new(next_index)),
+            session_start_index: next_index,
+
+            environments: storage.and_then(|storage| eframe::get_value(storage, "environments")).unwrap_or_default(),
+            active_environment: load_string("active_environment", ""),
+            show_environments: false,
+            oauth: storage.and_then(|storage| eframe::get_value(storage, "oauth")).unwrap_or_default(),
+            show_oauth: false,
+            show_encoder: false,
+            saved_requests: storage.and_then(|storage| eframe::get_value(storage, "saved_requests")).unwrap_or_default(),
+            show_saved: false,
+            default_headers: Vec::new(),
+            show_default_headers: false,
+            broken_config_files: Vec::new(),
+            editing_request: None,
+            encoder_operation: format::ENCODINGS[0].to_string(),
+            encoder_input: String::new(),
+
+            show_replay: false,
+            replay_parallel: false,
+            replay_concurrency: storage.and_then(|storage| eframe::get_value(storage, "replay_concurrency")).unwrap_or(DEFAULT_REPLAY_CONCURRENCY),
+            replay_running: Arc::new(Mutex::new(false)),
+            replay_total: 0,
+            replay_results: Arc::new(Mutex::new(Vec::new())),
+
+            event_stream: request::EventStream::default(),
 
             ui_error: None,
+            task_error: Arc::new(Mutex::new(None)),
+        };
+        app.reload_config();
+        app
+    }
+
+    // The JSON files win over eframe storage, which only covers installs that never wrote them
+    fn reload_config(&mut self) {
+        let mut errors = Vec::new();
+        self.broken_config_files.clear();
+        match config_files::load(ENVIRONMENTS_FILE) {
+            Ok(Some(environments)) => self.environments = environments,
+            Ok(None) => (),
+            Err(e) => {
+                errors.push(e);
+                self.broken_config_files.push(ENVIRONMENTS_FILE);
+            },
+        }
+        match config_files::load(SAVED_REQUESTS_FILE) {
+            Ok(Some(saved_requests)) => self.saved_requests = saved_requests,
+            Ok(None) => (),
+            Err(e) => {
+                errors.push(e);
+                self.broken_config_files.push(SAVED_REQUESTS_FILE);
+            },
+        }
+        match config_files::load(DEFAULT_HEADERS_FILE) {
+            Ok(Some(default_headers)) => self.default_headers = default_headers,
+            Ok(None) => (),
+            Err(e) => {
+                errors.push(e);
+                self.broken_config_files.push(DEFAULT_HEADERS_FILE);
+            },
+        }
+        if !errors.is_empty() {
+            self.ui_error = Some(errors.join("\n"));
+        }
+    }
+
+    fn save_config(&self) {
+        if !self.broken_config_files.contains(&ENVIRONMENTS_FILE)
+            && let Err(e) = config_files::save(ENVIRONMENTS_FILE, &self.environments) {
+            eprintln!("{}", e);
+        }
+        if !self.broken_config_files.contains(&SAVED_REQUESTS_FILE)
+            && let Err(e) = config_files::save(SAVED_REQUESTS_FILE, &self.saved_requests) {
+            eprintln!("{}", e);
+        }
+        if !self.broken_config_files.contains(&DEFAULT_HEADERS_FILE)
+            && let Err(e) = config_files::save(DEFAULT_HEADERS_FILE, &self.default_headers) {
+            eprintln!("{}", e);
         }
     }
     
-    fn send_request(&self, request_type: String, mut request_url: String, request_headers: String, request_body: String) -> Result<(), Box<dyn std::error::Error>> {
+    fn send_request(&self, request_type: String, request_url: String, request_headers: String, request_body: String, proxy: String, carry_over: CarryOver) -> Result<(), Box<dyn std::error::Error>> {
         let responses = Arc::clone(&self.request_responses);
         let is_loading = Arc::clone(&self.request_loading);
         let next_index = Arc::clone(&self.next_index);
@@ -73,6 +563,34 @@ impl App {
         let headers = Arc::clone(&self.show_responseheaders);
         let req_headers = Arc::clone(&self.show_requestheaders);
         let req_body = Arc::clone(&self.show_requestdetails);
+        let diagnostics = Arc::clone(&self.show_diagnostics);
+        let selected_index = Arc::clone(&self.selected_response_index);
+        let body_view = Arc::clone(&self.body_view);
+        let render_mode = Arc::clone(&self.render_mode);
+        let body_display_kb = Arc::clone(&self.body_display_kb);
+        let show_full_body = Arc::clone(&self.show_full_body);
+        let line_mode = self.ui_settings.long_line_mode.clone();
+        let long_lines = Arc::clone(&self.long_lines);
+        let task_error = Arc::clone(&self.task_error);
+        let log_path = self.log_path();
+        self.event_stream.events.lock().unwrap().clear();
+        self.event_stream.cancel.store(false, std::sync::atomic::Ordering::SeqCst);
+        self.upload_progress.sent.store(0, std::sync::atomic::Ordering::SeqCst);
+        self.upload_progress.total.store(0, std::sync::atomic::Ordering::SeqCst);
+        let options = request::RequestOptions {
+            proxy: proxy.trim().to_string(),
+            event_stream: Some(self.event_stream.clone()),
+            first_client: carry_over.first_client.clone(),
+            drop_bodyless_body: carry_over.drop_bodyless_body,
+            body_file: carry_over.body_file.clone(),
+            upload_progress: Some(self.upload_progress.clone()),
+            redirect_hops: self.capture_redirects.then(Default::default),
+            ..self.current_options()
+        };
+        let redirect_hops = options.redirect_hops.clone();
+        let proxy = options.proxy.clone();
+        let environment = carry_over.template.as_ref().map(|template| template.environment.clone())
+            .or_else(|| environment::find(&self.environments, &self.active_environment).map(|environment| environment.name.clone()));
 
         println!("Sending request headers: {}", request_headers);
         println!("Sending request body: {}", request_body);
@@ -80,89 +598,575 @@ impl App {
         if request_url.is_empty() {
             return Err("URL is empty".into());
         }
-        if request_url.contains("localhost") {
-            request_url = request_url.replace("localhost", "127.0.0.1");
-        }
-        if !request_url.starts_with("http") {
-            request_url = format!("http://{}", request_url);
+        // Credentials move into the auth settings, which aren't saved, so history never holds them.
+        // Same rule as request::send_request, they only become Basic auth when no auth is set
+        let mut url_credentials_note = None;
+        let request_url = match request::split_credentials(&request_url) {
+            Some((url, username, password)) => {
+                let mut auth_type = self.auth_type.lock().unwrap();
+                if *auth_type == "None" {
+                    *auth_type = "Basic".to_string();
+                    *self.auth_username.lock().unwrap() = username;
+                    *self.auth_password.lock().unwrap() = password;
+                }
+                else {
+                    url_credentials_note = Some(format!("Credentials in the URL ignored, {} auth is set\n", auth_type));
+                }
+                url
+            },
+            None => request_url,
+        };
+        let typed_url = request_url.clone();
+        let request_url = request::apply_default_scheme(&request_url, &self.default_scheme)?;
+        let auto_http = request::scheme_missing(&request_url);
+        let request_url = request::normalize_url(&request_url);
+        // History only keeps which default sets were applied, their values stay in the sets
+        let matching_sets = default_headers::matching(&self.default_headers, &request_url);
+        let default_header_sets: Vec<String> = matching_sets.iter().map(|set| set.label()).collect();
+        let merged_headers = self.merge_default_headers(&request_url, &request_headers);
+        // History keeps the {{name}} placeholders rather than what they resolve to, so a secret such as
+        // {{access_token}} isn't saved with it. Those headers are masked in the raw exchange too, and so
+        // is everything from the default sets, which may hold tokens as literal values
+        let sent_headers = self.substitute(&merged_headers);
+        let masked_headers: Vec<String> = merged_headers.lines()
+            .filter(|line| line.contains("{{"))
+            .chain(matching_sets.iter().flat_map(|set| set.headers.lines()))
+            .filter_map(|line| line.split_once(':').map(|(name, _)| name.trim().to_string()))
+            .filter(|name| !name.is_empty())
+            .collect();
+        // The body is kept as typed the same way, only the request that goes out has it filled in
+        let sent_body = self.substitute(&request_body);
+        let shown_body = (sent_body != request_body).then(|| request_body.clone());
+        let options = request::RequestOptions { masked_headers, shown_body, ..options };
+        let auth = self.current_auth();
+        let scheme = request_url.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase()).unwrap_or_default();
+
+        // Everything that changes what goes on the wire, auth included
+        let signature = format!("{} {}\n{}\n{}\n{}\n{:?}\n{}", request_type, request_url, sent_headers, sent_body, carry_over.body_file, auth, proxy);
+        if self.cache_enabled && !carry_over.refresh_cache {
+            let ttl = std::time::Duration::from_secs(self.cache_ttl_secs);
+            let hit = self.response_cache.lock().unwrap().iter()
+                .find(|(cached, stored, _)| *cached == signature && stored.elapsed() < ttl)
+                .map(|(_, _, index)| *index);
+            // The entry may have been deleted from history since
+            if let Some(cached) = hit.and_then(|index| self.get_response_by_index(index)) {
+                let index = {
+                    let mut next_index = self.next_index.lock().unwrap();
+                    *next_index += 1;
+                    *next_index - 1
+                };
+                let response = RequestResult {
+                    index,
+                    from_cache: true,
+                    duration_ms: 0,
+                    note: String::new(),
+                    assertions: carry_over.assertions,
+                    ..cached
+                };
+                self.request_responses.lock().unwrap().insert(0, response.clone());
+                *self.show_full_body.lock().unwrap() = false;
+                self.show_response(&response);
+                return Ok(());
+            }
         }
+        let response_cache = Arc::clone(&self.response_cache);
+        let cache_max_entries = if self.cache_enabled { self.cache_max_entries } else { 0 };
 
-        *is_loading.lock().unwrap() = true;
-        
-        let rt = match tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build() {
+        let rt = match runtime() {
                 Ok(rt) => rt,
                 Err(e) => {
                     eprintln!("Error building tokio runtime: {}", e);
                     return Err(e.into());
                 }
             };
-            
+
+        let loading = BusyGuard::set(&is_loading);
         thread::spawn(move || {
-            let current_index = {
-                let mut index = next_index.lock().unwrap();
-                let current = *index;
-                *index += 1;
-                current
-            };
+            let _loading = loading;
+            // A panic anywhere in the request must not leave the UI stuck on "Sending..."
+            let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let current_index = {
+                    let mut index = next_index.lock().unwrap();
+                    let current = *index;
+                    *index += 1;
+                    current
+                };
 
-            let response = match rt.block_on(async { request::send_request(request_type.clone(), request_url.clone(), request_headers.clone(), request_body.clone()).await }) {
-                Ok((status, headers, body)) => RequestResult {
-                    index: current_index,
-                    req_headers: request_headers,
-                    req_body: request_body,
-                    url: request_url,
-                    status,
-                    headers: headers.clone(),
-                    body: body.clone(),
-                    error: None,
-                },
-                Err((e, status, headers, tracebuilder)) => RequestResult {
-                    index: current_index,
-                    req_headers: request_headers,
-                    req_body: request_body,
-                    url: request_url,
-                    status,
-                    headers: headers.clone(),
-                    body: tracebuilder.clone(),
-                    error: Some(e.to_string()),
-                },
-            };
+                let started = std::time::Instant::now();
+                let result = rt.block_on(async { request::send_request(request_type.clone(), request_url.clone(), sent_headers, sent_body, auth, options).await });
+                let duration_ms = started.elapsed().as_millis() as u64;
+                let redirects = redirect_hops.map(|hops| hops.lock().unwrap().clone()).unwrap_or_default();
+                let mut response = match result {
+                    Ok(request::Response { status, headers, body, trace, raw_exchange, request_size, raw_body, trailers, body_error }) => RequestResult {
+                        index: current_index,
+                        method: request_type.clone(),
+                        req_headers: request_headers,
+                        req_body: request_body,
+                        url: request_url,
+                        scheme,
+                        auto_http,
+                        status,
+                        headers: headers.clone(),
+                        body: body.clone(),
+                        trace,
+                        body_read_failed: body_error.is_some(),
+                        default_header_sets: default_header_sets.clone(),
+                        error: body_error,
+                        raw_exchange,
+                        note: String::new(),
+                        proxy: proxy.clone(),
+                        duration_ms,
+                        request_size,
+                        body_base64: raw_body.map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes)).unwrap_or_default(),
+                        assertions: carry_over.assertions.clone(),
+                        template: carry_over.template.clone(),
+                        first_client: carry_over.first_client.clone(),
+                        drop_bodyless_body: carry_over.drop_bodyless_body,
+                        body_file: carry_over.body_file.clone(),
+                        typed_url: typed_url.clone(),
+                        trailers,
+                        redirects: redirects.clone(),
+                        environment: environment.clone(),
+                        from_cache: false,
+                    },
+                    Err((e, status, headers, tracebuilder)) => RequestResult {
+                        index: current_index,
+                        method: request_type.clone(),
+                        req_headers: request_headers,
+                        req_body: request_body,
+                        url: request_url,
+                        scheme,
+                        auto_http,
+                        status,
+                        headers: headers.clone(),
+                        body: tracebuilder.clone(),
+                        trace: tracebuilder,
+                        error: Some(e.to_string()),
+                        raw_exchange: String::new(),
+                        note: String::new(),
+                        proxy,
+                        duration_ms,
+                        request_size: 0,
+                        body_base64: String::new(),
+                        typed_url,
+                        assertions: carry_over.assertions,
+                        template: carry_over.template,
+                        first_client: carry_over.first_client,
+                        drop_bodyless_body: carry_over.drop_bodyless_body,
+                        body_file: carry_over.body_file,
+                        trailers: Vec::new(),
+                        redirects,
+                        environment,
+                        from_cache: false,
+                        body_read_failed: false,
+                        default_header_sets,
+                    },
+                };
+                if let Some(note) = &url_credentials_note {
+                    response.trace.insert_str(0, note);
+                }
+                if let Some(path) = &log_path
+                    && let Err(e) = append_log(path, &response.method, &response.url, &response.status, response.duration_ms, response.error.as_deref()) {
+                    *task_error.lock().unwrap() = Some(e);
+                }
+
+                let view = body_view.lock().unwrap().clone();
+                let mode = render_mode.lock().unwrap().clone();
+                let response_index = response.index;
+                let display_limit = *body_display_kb.lock().unwrap() * 1024;
+                let content_type = format::header_value(&response.headers.join("\n"), "Content-Type").unwrap_or_default();
+                let (response_body, response_long_lines) = format::guard_long_lines(format::render_body(&mode, &content_type, &view, format::truncate_for_display(&response.body, display_limit)), &line_mode);
+                let response_headers = response.headers.join("\n");
+                let request_headers = response.req_headers.clone();
+                let (request_body, request_long_lines) = format::guard_long_lines(format::format_body(&view, &response.req_body), &line_mode);
+                let response_trace = response.trace.clone();
+
+                // Only successful responses are worth answering with again, oldest go first past the limit
+                if cache_max_entries > 0 && response.error.is_none() {
+                    let mut cache = response_cache.lock().unwrap();
+                    cache.retain(|(cached, ..)| *cached != signature);
+                    cache.push((signature, std::time::Instant::now(), response_index));
+                    let excess = cache.len().saturating_sub(cache_max_entries);
+                    cache.drain(..excess);
+                }
 
-            let response_body = response.body.clone();
-            let response_headers = response.headers.join("\n");
-            let request_headers = response.req_headers.clone();
-            let request_body = response.req_body.clone();
-
-            responses.lock().unwrap().insert(0, response);
-            *is_loading.lock().unwrap() = false;
-            *details.lock().unwrap() = response_body;
-            *headers.lock().unwrap() = response_headers;
-            *req_headers.lock().unwrap() = request_headers;
-            *req_body.lock().unwrap() = request_body;
+                responses.lock().unwrap().insert(0, response);
+                *details.lock().unwrap() = response_body;
+                *headers.lock().unwrap() = response_headers;
+                *req_headers.lock().unwrap() = request_headers;
+                *req_body.lock().unwrap() = request_body;
+                *diagnostics.lock().unwrap() = response_trace;
+                *selected_index.lock().unwrap() = Some(response_index);
+                *show_full_body.lock().unwrap() = false;
+                *long_lines.lock().unwrap() = request_long_lines + response_long_lines;
+            }));
+            if let Err(panic) = outcome {
+                *task_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(format!("Request task panicked: {}", panic_message(&*panic)));
+            }
         });
         
         Ok(())
     }
 
+    // Resends every history entry oldest first and records how each response changed
+    fn replay_history(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut entries = self.request_responses.lock().unwrap().clone();
+        entries.sort_by_key(|entry| entry.index);
+        // History holds the headers and body as typed, with their {{name}} placeholders
+        for entry in &mut entries {
+            entry.req_headers = self.substitute(&self.merge_default_headers(&entry.url, &entry.req_headers));
+            entry.req_body = self.substitute(&entry.req_body);
+        }
+        let results = Arc::clone(&self.replay_results);
+        let running = Arc::clone(&self.replay_running);
+        let task_error = Arc::clone(&self.task_error);
+        let auth = self.current_auth();
+        let options = self.current_options();
+        let log_path = self.log_path();
+        // Sequential replay is just a single worker
+        let concurrency = if self.replay_parallel { self.replay_concurrency.max(1) } else { 1 };
+
+        let rt = runtime()?;
+        self.replay_total = entries.len();
+        results.lock().unwrap().clear();
+
+        let running = BusyGuard::set(&running);
+        thread::spawn(move || {
+            let _running = running;
+            // Workers pull from a shared queue so at most `concurrency` requests are in flight
+            let queue = Arc::new(Mutex::new(std::collections::VecDeque::from(entries)));
+            rt.block_on(async {
+                let workers: Vec<_> = (0..concurrency).map(|_| {
+                    let queue = Arc::clone(&queue);
+                    let results = Arc::clone(&results);
+                    let task_error = Arc::clone(&task_error);
+                    let auth = auth.clone();
+                    let options = options.clone();
+                    let log_path = log_path.clone();
+                    tokio::spawn(async move {
+                        loop {
+                            let Some(entry) = queue.lock().unwrap().pop_front() else { break };
+                            let outcome = replay_entry(entry, auth.clone(), options.clone(), log_path.as_deref(), &task_error).await;
+                            results.lock().unwrap().push(outcome);
+                        }
+                    })
+                }).collect();
+                // tokio catches a panicking task, the other workers carry on with the queue
+                for worker in workers {
+                    if let Err(e) = worker.await
+                        && e.is_panic() {
+                        *task_error.lock().unwrap() = Some(format!("Replay task panicked: {}", panic_message(&*e.into_panic())));
+                    }
+                }
+            });
+        });
+
+        Ok(())
+    }
+
+    fn log_path(&self) -> Option<String> {
+        let path = self.log_path.trim();
+        if self.log_enabled && !path.is_empty() { Some(path.to_string()) } else { None }
+    }
+
+    fn current_auth(&self) -> auth::Auth {
+        auth::Auth {
+            auth_type: self.auth_type.lock().unwrap().clone(),
+            username: self.auth_username.lock().unwrap().clone(),
+            password: self.auth_password.lock().unwrap().clone(),
+            token: self.auth_token.lock().unwrap().clone(),
+        }
+    }
+
+    fn current_options(&self) -> request::RequestOptions {
+        request::RequestOptions {
+            dns_provider: self.dns_provider.lock().unwrap().clone(),
+            exact_headers: *self.exact_headers.lock().unwrap(),
+            pool_max_idle_per_host: *self.pool_max_idle.lock().unwrap(),
+            raw_chunk_framing: *self.raw_chunk_framing.lock().unwrap(),
+            probe_retries: *self.probe_retries.lock().unwrap(),
+            probe_retry_delay_ms: *self.probe_retry_delay_ms.lock().unwrap(),
+            skip_probe: *self.skip_probe.lock().unwrap(),
+            max_response_mb: *self.max_response_mb.lock().unwrap(),
+            proxy: String::new(),
+            event_stream: None,
+            tls_min: tls_setting(&self.tls_min.lock().unwrap()),
+            tls_max: tls_setting(&self.tls_max.lock().unwrap()),
+            first_client: String::new(),
+            drop_bodyless_body: false,
+            body_file: String::new(),
+            upload_progress: None,
+            send_delay_ms: *self.send_delay_ms.lock().unwrap(),
+            throttle_bytes_per_sec: *self.throttle_bytes_per_sec.lock().unwrap(),
+            root_ca_file: self.root_ca_file.trim().to_string(),
+            https_only: self.https_only,
+            redirect_hops: None,
+            masked_headers: Vec::new(),
+            shown_body: None,
+        }
+    }
+
+    // A request from the New Request form, nothing to carry over yet besides the form's own settings
+    fn draft_carry_over(&self) -> CarryOver {
+        let first_client = self.first_client.lock().unwrap().clone();
+        CarryOver {
+            template: self.current_template(),
+            first_client: if first_client == request::CLIENT_TIERS[0] { String::new() } else { first_client },
+            drop_bodyless_body: !self.allow_bodyless_body,
+            body_file: self.body_file.trim().to_string(),
+            ..Default::default()
+        }
+    }
+
+    // Sends a saved request as it is, with its counter (if any) taking the next value
+    fn send_saved(&mut self, position: usize) {
+        let request = self.saved_requests[position].clone();
+        // The counter goes into the body as typed when it's JSON with its placeholders still in, so
+        // they stay out of history. Placeholders standing in for whole values need filling in first
+        let sent = request.fill_counter(&request.body).or_else(|_| request.fill_counter(&self.substitute(&request.body))).map_err(|e| e.into())
+            .and_then(|body| self.resolve_url(&request.url).map_err(|e| e.into()).map(|url| (url, body)))
+            .and_then(|(url, body)| self.send_request(request.method.clone(), url, request.headers.clone(), body, String::new(), CarryOver::default()));
+        match sent {
+            Ok(_) => {
+                // A request that never went out doesn't use up a counter value
+                if !request.counter_path.trim().is_empty() {
+                    self.saved_requests[position].counter_next += 1;
+                }
+                self.ui_error = None;
+            },
+            Err(e) => self.ui_error = Some(format!("Error sending {}: {}", request.name, e)),
+        }
+    }
+
+    // Fills the New Request form, custom verbs go through the Custom... field
+    fn load_draft(&self, method: &str, url: &str, headers: &str, body: &str) {
+        if matches!(method, "GET" | "POST" | "PUT" | "PATCH" | "DELETE") {
+            *self.request_type.lock().unwrap() = method.to_string();
+        }
+        else {
+            *self.request_type.lock().unwrap() = "Custom...".to_string();
+            *self.custom_method.lock().unwrap() = method.to_string();
+        }
+        *self.request_url.lock().unwrap() = url.to_string();
+        *self.request_headers.lock().unwrap() = headers.to_string();
+        *self.request_body.lock().unwrap() = body.to_string();
+    }
+
+    fn selected_method(&self) -> String {
+        let request_type = self.request_type.lock().unwrap().clone();
+        if request_type == "Custom..." {
+            self.custom_method.lock().unwrap().trim().to_string()
+        }
+        else {
+            request_type
+        }
+    }
+
+    // The URL field holds only a path when the active environment has a base URL
+    fn resolved_url(&self) -> Result<String, String> {
+        self.resolve_url(&self.request_url.lock().unwrap())
+    }
+
+    fn resolve_url(&self, request_url: &str) -> Result<String, String> {
+        let request_url = self.substitute(request_url);
+        match environment::find(&self.environments, &self.active_environment) {
+            Some(environment) if !environment.base_url.trim().is_empty() => request::join_base_url(&environment.base_url, &request_url),
+            _ => Ok(request_url),
+        }
+    }
+
+    // Default headers for the host go under the request's own, merged in again on every send
+    fn merge_default_headers(&self, request_url: &str, request_headers: &str) -> String {
+        let sets = default_headers::matching(&self.default_headers, request_url);
+        if sets.is_empty() {
+            return request_headers.to_string();
+        }
+        default_headers::merge(sets.iter().map(|set| set.headers.as_str()).chain([request_headers]))
+    }
+
+    // {{name}} placeholders from the active environment's variables
+    fn substitute(&self, text: &str) -> String {
+        environment::substitute(text, environment::find(&self.environments, &self.active_environment))
+    }
+
+    // None when the URL field is sent as typed
+    fn current_template(&self) -> Option<RequestTemplate> {
+        let request_url = self.request_url.lock().unwrap().trim().to_string();
+        let request_url = request::split_credentials(&request_url).map(|(url, ..)| url).unwrap_or(request_url);
+        let relative = !request_url.starts_with("http://") && !request_url.starts_with("https://");
+        match environment::find(&self.environments, &self.active_environment) {
+            Some(environment) if (!environment.base_url.trim().is_empty() && relative) || request_url.contains("{{") => Some(RequestTemplate {
+                environment: environment.name.clone(),
+                url: request_url,
+            }),
+            _ => None,
+        }
+    }
+
     fn get_response_by_index(&self, index: usize) -> Option<RequestResult> {
         let responses = self.request_responses.lock().unwrap();
         responses.iter().find(|r| r.index == index).cloned()
     }
+
+    fn show_response(&self, response: &RequestResult) {
+        let view = self.body_view.lock().unwrap().clone();
+        let display_limit = if *self.show_full_body.lock().unwrap() { usize::MAX } else { *self.body_display_kb.lock().unwrap() * 1024 };
+        *self.show_requestheaders.lock().unwrap() = response.req_headers.clone();
+        let line_mode = self.ui_settings.long_line_mode.clone();
+        let mode = self.render_mode.lock().unwrap().clone();
+        let content_type = format::header_value(&response.headers.join("\n"), "Content-Type").unwrap_or_default();
+        let (request_body, request_long_lines) = format::guard_long_lines(format::format_body(&view, &response.req_body), &line_mode);
+        let (response_body, response_long_lines) = format::guard_long_lines(format::render_body(&mode, &content_type, &view, format::truncate_for_display(&response.body, display_limit)), &line_mode);
+        *self.show_requestdetails.lock().unwrap() = request_body;
+        *self.show_responsedetails.lock().unwrap() = response_body;
+        *self.long_lines.lock().unwrap() = request_long_lines + response_long_lines;
+        *self.show_responseheaders.lock().unwrap() = response.headers.join("\n");
+        *self.show_diagnostics.lock().unwrap() = response.trace.clone();
+        *self.selected_response_index.lock().unwrap() = Some(response.index);
+    }
+
+    fn watch_values(&mut self, response: &RequestResult) -> Option<Vec<String>> {
+        if response.error.is_some() || self.watches.is_empty() {
+            return None;
+        }
+        self.watch_cache.entry(response.index)
+            .or_insert_with(|| format::watch_values(&response.body, &self.watches))
+            .clone()
+    }
+
+    fn move_response(&self, from_index: usize, to_index: usize) {
+        let mut responses = self.request_responses.lock().unwrap();
+        let from = responses.iter().position(|r| r.index == from_index);
+        let to = responses.iter().position(|r| r.index == to_index);
+        if let (Some(from), Some(to)) = (from, to) {
+            let response = responses.remove(from);
+            // Removing it shifted everything after it up one, so it still lands in front of the target
+            let to = if from < to { to - 1 } else { to };
+            responses.insert(to, response);
+        }
+    }
 }
 
 impl eframe::App for App {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, "request_responses", &*self.request_responses.lock().unwrap());
+        eframe::set_value(storage, "ui_settings", &self.ui_settings);
+        eframe::set_value(storage, "body_display_kb", &*self.body_display_kb.lock().unwrap());
+        eframe::set_value(storage, "watches", &self.watches);
+        eframe::set_value(storage, "format_on_paste", &self.format_on_paste);
+        eframe::set_value(storage, "log_enabled", &self.log_enabled);
+        eframe::set_value(storage, "log_path", &self.log_path);
+        eframe::set_value(storage, "history_order", &self.history_order);
+        eframe::set_value(storage, "history_grouping", &self.history_grouping);
+        eframe::set_value(storage, "history_autoscroll", &self.history_autoscroll);
+        eframe::set_value(storage, "trace_line_numbers", &self.trace_line_numbers);
+        eframe::set_value(storage, "decode_display", &self.decode_display);
+        eframe::set_value(storage, "request_collapsed", &self.request_collapsed);
+        eframe::set_value(storage, "allow_bodyless_body", &self.allow_bodyless_body);
+        eframe::set_value(storage, "capture_redirects", &self.capture_redirects);
+        eframe::set_value(storage, "hex_bytes_per_row", &self.hex_inspector.bytes_per_row);
+        eframe::set_value(storage, "pool_max_idle", &*self.pool_max_idle.lock().unwrap());
+        eframe::set_value(storage, "probe_retries", &*self.probe_retries.lock().unwrap());
+        eframe::set_value(storage, "probe_retry_delay_ms", &*self.probe_retry_delay_ms.lock().unwrap());
+        eframe::set_value(storage, "skip_probe", &*self.skip_probe.lock().unwrap());
+        eframe::set_value(storage, "max_response_mb", &*self.max_response_mb.lock().unwrap());
+        eframe::set_value(storage, "root_ca_file", &self.root_ca_file);
+        eframe::set_value(storage, "https_only", &self.https_only);
+        eframe::set_value(storage, "default_scheme", &self.default_scheme);
+        eframe::set_value(storage, "cache_enabled", &self.cache_enabled);
+        eframe::set_value(storage, "cache_ttl_secs", &self.cache_ttl_secs);
+        eframe::set_value(storage, "cache_max_entries", &self.cache_max_entries);
+        eframe::set_value(storage, "replay_concurrency", &self.replay_concurrency);
+        eframe::set_value(storage, "environments", &self.environments);
+        eframe::set_value(storage, "oauth", &self.oauth);
+        eframe::set_value(storage, "saved_requests", &self.saved_requests);
+        eframe::set_value(storage, "active_environment", &self.active_environment);
+        self.save_config();
+
+        // New Request modal draft, kept separately from history
+        eframe::set_value(storage, "draft_request_type", &*self.request_type.lock().unwrap());
+        eframe::set_value(storage, "draft_custom_method", &*self.custom_method.lock().unwrap());
+        let draft_url = self.request_url.lock().unwrap().clone();
+        eframe::set_value(storage, "draft_request_url", &request::split_credentials(&draft_url).map(|(url, ..)| url).unwrap_or(draft_url));
+        eframe::set_value(storage, "draft_request_headers", &*self.request_headers.lock().unwrap());
+        eframe::set_value(storage, "draft_request_body", &*self.request_body.lock().unwrap());
+        eframe::set_value(storage, "draft_body_file", &self.body_file);
+        eframe::set_value(storage, "draft_request_proxy", &*self.request_proxy.lock().unwrap());
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let is_loading = *self.request_loading.lock().unwrap();
         let responses = self.request_responses.lock().unwrap().clone();
+        if let Some(error) = self.task_error.lock().unwrap().take() {
+            self.ui_error = Some(error);
+        }
+
+        self.ui_settings.apply_theme(ctx);
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.add_space(20.0);
-            ui.columns(2, |columns| {
+            let mut split = self.ui_settings.split;
+            let split_before = split;
+            ui_settings::split_columns(ui, &mut split, |columns| {
 
                 /* Headings */
                 columns[0].heading("Nancy API Debugger");
+                let session_requests = *self.next_index.lock().unwrap() - self.session_start_index;
+                let session_failed = responses.iter().filter(|r| r.index >= self.session_start_index && r.error.is_some()).count();
+                let session_finished = responses.iter().filter(|r| r.index >= self.session_start_index).count();
+                columns[0].horizontal(|ui| {
+                    ui.small(format!("Requests this session: {} ({} succeeded, {} failed)", session_requests, session_finished - session_failed, session_failed));
+                    if self.https_only {
+                        https_only_badge(ui);
+                    }
+                });
+                columns[0].horizontal(|ui| {
+                    ui.checkbox(&mut self.log_enabled, "Log requests to");
+                    ui.add(egui::TextEdit::singleline(&mut self.log_path).desired_width(220.0))
+                        .on_hover_text("Appends method, URL, status and timing of every request as a JSON line");
+                });
+                columns[0].horizontal(|ui| {
+                    ui.label("Theme:");
+                    egui::ComboBox::from_id_salt("theme_combo")
+                        .selected_text(self.ui_settings.theme.as_str())
+                        .show_ui(ui, |ui| {
+                            for theme in ui_settings::THEMES {
+                                ui.selectable_value(&mut self.ui_settings.theme, theme.to_string(), theme);
+                            }
+                        });
+                    if ui.small_button("Reset UI").on_hover_text("Theme, text size, column split, history density and long line handling back to their defaults").clicked() {
+                        self.ui_settings = ui_settings::UiSettings::default();
+                        let selected = *self.selected_response_index.lock().unwrap();
+                        if let Some(response) = selected.and_then(|index| self.get_response_by_index(index)) {
+                            self.show_response(&response);
+                        }
+                    }
+                });
+                if let Some(dir) = config_files::dir() {
+                    columns[0].horizontal(|ui| {
+                        ui.small(format!("Config: {}", dir.display()))
+                            .on_hover_text(format!("{}, {} and {} can be edited by hand, then reloaded", ENVIRONMENTS_FILE, SAVED_REQUESTS_FILE, DEFAULT_HEADERS_FILE));
+                        if ui.small_button("Reload").clicked() {
+                            self.reload_config();
+                        }
+                    });
+                }
+
+                // Favorites bar, pinned saved requests sent with one click
+                let mut send_saved = None;
+                columns[0].horizontal_wrapped(|ui| {
+                    for (position, request) in self.saved_requests.iter().enumerate().filter(|(_, request)| request.pinned) {
+                        if ui.button(&request.name).on_hover_text(format!("{} {}", request.method, request.url)).clicked() {
+                            send_saved = Some(position);
+                        }
+                    }
+                    if ui.small_button(format!("Saved ({})", self.saved_requests.len())).clicked() {
+                        self.show_saved = true;
+                    }
+                });
+                if let Some(position) = send_saved {
+                    self.send_saved(position);
+                }
                 egui::Frame::new().show(&mut columns[1], |ui| {
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
                         ui.add_space(20.0);
@@ -172,9 +1176,23 @@ impl eframe::App for App {
                             self.show_newrequest = true;
                             self.set_focus = "newrequest".to_string();
                         }
+                        if ui.add_sized([120.0, 25.0], egui::Button::new("Replay History")).clicked() {
+                            self.show_replay = true;
+                        }
+                        if ui.add_sized([120.0, 25.0], egui::Button::new("OAuth2 Token")).clicked() {
+                            self.show_oauth = true;
+                        }
+                        if ui.add_sized([120.0, 25.0], egui::Button::new("Encode / Decode")).clicked() {
+                            self.show_encoder = true;
+                        }
                         if is_loading {
                             ui.add_space(10.0);
                             ui.spinner();
+                            let total = self.upload_progress.total.load(std::sync::atomic::Ordering::SeqCst);
+                            if total > 0 {
+                                let sent = self.upload_progress.sent.load(std::sync::atomic::Ordering::SeqCst);
+                                ui.label(format!("Uploading {} / {} KB", sent / 1024, total / 1024));
+                            }
                         }
                     });
                     if let Some(error) = &self.ui_error {
@@ -187,46 +1205,210 @@ impl eframe::App for App {
                 });
                 columns[0].add_space(40.0);
                 egui::ScrollArea::vertical().id_salt("c1").show(&mut columns[0], |ui| {
-                    ui.heading("Request History");
+                    ui.horizontal(|ui| {
+                        ui.heading("Request History");
+                        ui.add_space(10.0);
+                        egui::ComboBox::from_id_salt("history_order_combo")
+                            .selected_text(self.history_order.as_str())
+                            .show_ui(ui, |ui| {
+                                for order in ["Newest first", "Oldest first", "Status", "Duration"] {
+                                    ui.selectable_value(&mut self.history_order, order.to_string(), order);
+                                }
+                            });
+                        egui::ComboBox::from_id_salt("history_grouping_combo")
+                            .selected_text(self.history_grouping.as_str())
+                            .show_ui(ui, |ui| {
+                                for grouping in HISTORY_GROUPINGS {
+                                    ui.selectable_value(&mut self.history_grouping, grouping.to_string(), grouping);
+                                }
+                            })
+                            .response.on_hover_text("Group entries under the host, or host and path, they were sent to");
+                        for density in HISTORY_DENSITIES {
+                            ui.selectable_value(&mut self.ui_settings.history_density, density.to_string(), density);
+                        }
+                        ui.checkbox(&mut self.history_autoscroll, "Auto-scroll")
+                            .on_hover_text("Scroll to each new response as it arrives");
+                    });
                     ui.add_space(10.0);
-                    
-                    for response in responses.iter() {
-                        ui.group(|ui| {
-                            ui.horizontal(|ui| {
-                                ui.label(format!("{}", response.url));
-                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
-                                    if ui.add_sized([120.0, 20.0], egui::Button::new("View Response")).clicked() {
-                                        *self.show_requestheaders.lock().unwrap() = response.req_headers.clone();
-                                        *self.show_requestdetails.lock().unwrap() = response.req_body.clone();
-                                        *self.show_responsedetails.lock().unwrap() = response.body.clone();
-                                        *self.show_responseheaders.lock().unwrap() = response.headers.join("\n");
-                                        self.selected_response_index = Some(response.index);
-                                    }
-                                    if ui.add_sized([80.0, 20.0], egui::Button::new("Resend")).clicked() {
-                                        match self.send_request(self.request_type.lock().unwrap().clone(), response.url.clone(), response.req_headers.clone(), response.req_body.clone()) {
-                                            Ok(_) => {
-                                                self.ui_error = None;
-                                                self.selected_response_index = None;
-                                            },
-                                            Err(e) => {
-                                                let error_msg = format!("Error sending request: {}", e);
-                                                eprintln!("{}", error_msg);
+
+                    // Display order only, entries keep their index and stored position
+                    let mut ordered: Vec<&RequestResult> = responses.iter().collect();
+                    match self.history_order.as_str() {
+                        "Oldest first" => ordered.reverse(),
+                        "Status" => ordered.sort_by_key(|response| response.status.split_whitespace().next().and_then(|code| code.parse::<u16>().ok()).unwrap_or(u16::MAX)),
+                        "Duration" => ordered.sort_by_key(|response| std::cmp::Reverse(response.duration_ms)),
+                        _ => (),
+                    }
+                    // Dragging rearranges the stored order, which is only what's shown newest first and ungrouped
+                    let grouping = self.history_grouping.clone();
+                    let compact = self.ui_settings.history_density == "Compact";
+                    let draggable = self.history_order == "Newest first" && grouping == HISTORY_GROUPINGS[0];
+                    let newest = responses.iter().map(|response| response.index).max();
+                    let scroll_to_newest = self.history_autoscroll && newest > self.history_newest_seen;
+                    self.history_newest_seen = newest.max(self.history_newest_seen);
+
+                    let mut dropped: Option<(usize, usize)> = None;
+                    let selected_index = *self.selected_response_index.lock().unwrap();
+                    let mut show_entry = |ui: &mut egui::Ui, response: &RequestResult| {
+                        // The entry shown in the details panel gets the selection color
+                        let mut frame = egui::Frame::group(ui.style());
+                        if selected_index == Some(response.index) {
+                            frame = frame.stroke(egui::Stroke::new(2.0, ui.visuals().selection.bg_fill)).fill(ui.visuals().faint_bg_color);
+                        }
+                        let group = if compact {
+                            // One line per entry, clicking it shows the details like View Response
+                            let group = frame.inner_margin(egui::Margin::symmetric(6, 2)).show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    if draggable {
+                                        ui.dnd_drag_source(egui::Id::new(("history_drag", response.index)), response.index, |ui| {
+                                            ui.label("☰");
+                                        }).response.on_hover_text("Drag to reorder");
+                                    }
+                                    method_badge(ui, &response.method);
+                                    let code = response.status.split_whitespace().next().unwrap_or("-");
+                                    let code = egui::RichText::new(code).monospace();
+                                    ui.label(if response.error.is_some() { code.color(egui::Color32::RED) } else { code });
+                                    if response.body_read_failed {
+                                        body_read_badge(ui);
+                                    }
+                                    let url = format::truncate_for_display(&response.url, COMPACT_URL_BYTES);
+                                    ui.label(if url.len() < response.url.len() { format!("{}…", url) } else { url.to_string() });
+                                    if response.duration_ms > 0 {
+                                        ui.weak(format!("{} ms", response.duration_ms));
+                                    }
+                                    if response.from_cache {
+                                        ui.weak("(cached)");
+                                    }
+                                });
+                            });
+                            let row = group.response.interact(egui::Sense::click()).on_hover_text(match &response.error {
+                                Some(error) => format!("{}\n{}", response.url, error),
+                                None => response.url.clone(),
+                            });
+                            if row.clicked() {
+                                *self.show_full_body.lock().unwrap() = false;
+                                self.show_response(response);
+                            }
+                            group
+                        }
+                        else {
+                            frame.show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    if draggable {
+                                        ui.dnd_drag_source(egui::Id::new(("history_drag", response.index)), response.index, |ui| {
+                                            ui.label("☰");
+                                        }).response.on_hover_text("Drag to reorder");
+                                    }
+                                    method_badge(ui, &response.method);
+                                    scheme_badge(ui, response);
+                                    if let Some(environment) = &response.environment {
+                                        environment_badge(ui, environment);
+                                    }
+                                    ui.label(&response.url);
+                                    if !response.note.is_empty() {
+                                        ui.label("📝").on_hover_text(&response.note);
+                                    }
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                                        if ui.add_sized([120.0, 20.0], egui::Button::new("View Response")).clicked() {
+                                            *self.show_full_body.lock().unwrap() = false;
+                                            self.show_response(response);
+                                        }
+                                        if ui.add_sized([80.0, 20.0], egui::Button::new("Resend")).clicked() {
+                                            match self.send_request(response.method.clone(), response.url.clone(), response.req_headers.clone(), response.req_body.clone(), response.proxy.clone(), response.carry_over()) {
+                                                Ok(_) => {
+                                                    self.ui_error = None;
+                                                    *self.selected_response_index.lock().unwrap() = None;
+                                                },
+                                                Err(e) => {
+                                                    let error_msg = format!("Error sending request: {}", e);
+                                                    eprintln!("{}", error_msg);
+                                                }
                                             }
                                         }
+                                        if ui.add_sized([130.0, 20.0], egui::Button::new("Resend with changes")).clicked() {
+                                            self.resend_override = Some(response.clone());
+                                        }
+                                    });
+                                });
+                                ui.add_space(10.0);
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("Status: {}", response.status));
+                                    if response.duration_ms > 0 {
+                                        ui.label(format!("{} ms", response.duration_ms));
+                                    }
+                                    if let Some(cache) = format::cache_info(&response.status, &response.headers)
+                                        && cache.cached {
+                                        badge(ui, "cached", egui::Color32::from_rgb(90, 90, 160)).on_hover_text(cache.summary);
+                                    }
+                                    if response.from_cache {
+                                        badge(ui, "from cache", egui::Color32::from_rgb(120, 90, 160)).on_hover_text("Answered from the response cache, not sent");
+                                    }
+                                    if response.body_read_failed {
+                                        body_read_badge(ui);
                                     }
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                                        if let Some(error) = &response.error {
+                                            ui.colored_label(egui::Color32::RED, error);
+                                        }
+                                    });
                                 });
-                            });
-                            ui.add_space(10.0);
-                            ui.horizontal(|ui| {
-                                ui.label(format!("Status: {}", response.status));
-                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
-                                    if let Some(error) = &response.error {
-                                        ui.colored_label(egui::Color32::RED, format!("{}", error));
+                                if let Some(values) = self.watch_values(response) {
+                                    ui.horizontal_wrapped(|ui| {
+                                        for (watch, value) in self.watches.iter().zip(values) {
+                                            ui.small(format!("{} = {}", watch, value));
+                                        }
+                                    });
+                                }
+                            })
+                        };
+                        if scroll_to_newest && Some(response.index) == newest {
+                            group.response.scroll_to_me(None);
+                        }
+
+                        // Drop onto another entry to take its place
+                        if let Some(dragged) = group.response.dnd_hover_payload::<usize>()
+                            && *dragged != response.index {
+                            let rect = group.response.rect;
+                            ui.painter().hline(rect.x_range(), rect.top(), egui::Stroke::new(2.0, ui.visuals().selection.bg_fill));
+                        }
+                        if let Some(dragged) = group.response.dnd_release_payload::<usize>() {
+                            dropped = Some((*dragged, response.index));
+                        }
+                        ui.add_space(if compact { 2.0 } else { 10.0 });
+                    };
+                    if grouping == HISTORY_GROUPINGS[0] {
+                        for response in ordered {
+                            show_entry(ui, response);
+                        }
+                    }
+                    else {
+                        // Groups appear in the order of their first entry, so the order setting still applies
+                        let mut groups: Vec<(String, Vec<&RequestResult>)> = Vec::new();
+                        for response in ordered {
+                            let key = history_group(&response.url, grouping == "Host + path");
+                            match groups.iter_mut().find(|(group, _)| *group == key) {
+                                Some((_, entries)) => entries.push(response),
+                                None => groups.push((key, vec![response])),
+                            }
+                        }
+                        for (key, entries) in groups {
+                            let failed = entries.iter().filter(|response| response.error.is_some()).count();
+                            let title = match failed {
+                                0 => format!("{} ({})", key, entries.len()),
+                                failed => format!("{} ({}, {} failed)", key, entries.len(), failed),
+                            };
+                            egui::CollapsingHeader::new(title)
+                                .id_salt(("history_group", &key))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    for response in entries {
+                                        show_entry(ui, response);
                                     }
                                 });
-                            });
-                        });
-                        ui.add_space(10.0);
+                        }
+                    }
+                    if let Some((from_index, to_index)) = dropped {
+                        self.move_response(from_index, to_index);
                     }
                     
                     if responses.is_empty() && !is_loading {
@@ -235,69 +1417,355 @@ impl eframe::App for App {
                 });
 
                 /* Details */
-                if !self.show_responsedetails.lock().unwrap().is_empty() {
+                // An empty body (204, HEAD) or no headers is still a response worth showing
+                let has_selection = self.selected_response_index.lock().unwrap().is_some_and(|index| responses.iter().any(|response| response.index == index));
+                if has_selection {
                     columns[1].add_space(40.0);
 
 
                     /* Request */
-                    egui::ScrollArea::vertical().id_salt("c2_req").show(&mut columns[1], |ui| {
+                    // Collapsed, the response gets the height the request details took
+                    columns[1].horizontal(|ui| {
                         ui.heading("Request");
-                        ui.add_space(10.0);
-                        if let Some(index) = self.selected_response_index {
-                            if let Some(response) = self.get_response_by_index(index) {
+                        let toggle = if self.request_collapsed { "Show" } else { "Hide" };
+                        if ui.small_button(toggle).on_hover_text("Hide the request details to give the response the full height").clicked() {
+                            self.request_collapsed = !self.request_collapsed;
+                        }
+                        if self.request_collapsed
+                            && let Some(response) = self.selected_response_index.lock().unwrap().and_then(|index| responses.iter().find(|response| response.index == index)) {
+                            method_badge(ui, &response.method);
+                            ui.label(&response.url);
+                            ui.label(&response.status);
+                        }
+                    });
+                    columns[1].add_space(10.0);
+                    if !self.request_collapsed {
+                        egui::ScrollArea::vertical().id_salt("c2_req").show(&mut columns[1], |ui| {
+                            let selected_index = *self.selected_response_index.lock().unwrap();
+                            if let Some(index) = selected_index
+                                && let Some(response) = self.get_response_by_index(index) {
                                 ui.horizontal(|ui| {
-                                    ui.label(&response.url);
+                                    scheme_badge(ui, &response);
+                                    if self.decode_display {
+                                        ui.label(format::percent_decode(&response.url));
+                                    }
+                                    else {
+                                        ui.label(&response.url);
+                                    }
                                     ui.add_space(5.0);
                                     ui.label(&response.status);
+                                    if response.body_read_failed {
+                                        body_read_badge(ui);
+                                    }
+                                    ui.checkbox(&mut self.decode_display, "Decode")
+                                        .on_hover_text("Show the URL percent-decoded, the request is still sent encoded");
                                 });
-                            }
-                        }
-                    });
-                    columns[1].add_space(10.0);
-
-                    // Headers
-                    if !self.show_requestheaders.lock().unwrap().is_empty() {
-                        columns[1].add(egui::Label::new("Headers"));
-                        egui::ScrollArea::vertical()
-                        .id_salt("req_headers")
-                        .max_height(150.0)
-                        .show(&mut columns[1], |ui| {
-                            ui.add(
-                                egui::TextEdit::multiline(&mut *self.show_requestheaders.lock().unwrap())
-                                    .id_salt("req_headers_text")
-                                    .desired_width(f32::INFINITY)
-                                    .desired_rows(3)
-                                    .interactive(false)
-                            );
-                        });
-                        columns[1].add_space(10.0);
-                    }
-
-                    // Body
-                    if !self.show_requestdetails.lock().unwrap().is_empty() {
-                        columns[1].add(egui::Label::new("Body"));
-                        egui::ScrollArea::vertical()
-                            .id_salt("req_body")
-                            .max_height(150.0)
-                            .show(&mut columns[1], |ui| {
-                                ui.add(
-                                egui::TextEdit::multiline(&mut *self.show_requestdetails.lock().unwrap())
-                                    .id_salt("req_body_text")
-                                    .desired_width(f32::INFINITY)
-                                    .desired_rows(5)
-                                    .interactive(true)
-                            );
-                        });
+                                if !response.proxy.is_empty() {
+                                    ui.label(format!("Via proxy {}", response.proxy));
+                                }
+                                if !response.first_client.is_empty() {
+                                    ui.label(format!("Started at the {} client", response.first_client));
+                                }
+                                if !response.body_file.is_empty() {
+                                    ui.label(format!("Body streamed from {}", response.body_file));
+                                }
+                                if response.drop_bodyless_body && !response.req_body.is_empty() && request::usually_bodyless(&response.method) {
+                                    ui.label(format!("Body not sent, {} bodies were left out", response.method));
+                                }
+                                if !response.default_header_sets.is_empty() {
+                                    ui.label(format!("Default headers from: {}", response.default_header_sets.join(", ")))
+                                        .on_hover_text("Merged in from Default Headers when sent, and again on resend");
+                                }
+                                if let Some(template) = &response.template {
+                                    ui.label(format!("Template: {} in environment '{}'", template.url, template.environment))
+                                        .on_hover_text("The URL above is what this resolved to and was sent as");
+                                }
+                                if response.body_read_failed
+                                    && let Some(error) = &response.error {
+                                    ui.colored_label(egui::Color32::RED, format!("{}, the body below is only what arrived", error));
+                                }
+                                if response.error.is_none() || response.body_read_failed {
+                                    ui.label(format!("Request ~{} bytes, response body {} bytes", response.request_size, response.body.len()))
+                                        .on_hover_text("Request size is the request line, headers and body as sent, counting CRLF line endings");
+                                }
+                                let sent_request = response.raw_exchange.split("\n\n").next().unwrap_or("");
+                                let modifications = format::request_modifications(&response.typed_url, &response.url, &response.req_headers, sent_request);
+                                if !modifications.is_empty() {
+                                    egui::CollapsingHeader::new(format!("Modifications applied ({})", modifications.len()))
+                                        .id_salt(("modifications", index))
+                                        .show(ui, |ui| {
+                                            for modification in modifications {
+                                                ui.label(modification);
+                                            }
+                                        });
+                                }
+                                if response.auto_http {
+                                    ui.horizontal(|ui| {
+                                        ui.colored_label(egui::Color32::from_rgb(220, 140, 40), "No scheme was given, so this went out as plain HTTP");
+                                        if ui.button("Retry as HTTPS").clicked() {
+                                            let https_url = response.url.replacen("http://", "https://", 1);
+                                            match self.send_request(response.method.clone(), https_url, response.req_headers.clone(), response.req_body.clone(), response.proxy.clone(), response.carry_over()) {
+                                                Ok(_) => self.ui_error = None,
+                                                Err(e) => {
+                                                    let error_msg = format!("Error sending request: {}", e);
+                                                    eprintln!("{}", error_msg);
+                                                    self.ui_error = Some(error_msg);
+                                                }
+                                            }
+                                        }
+                                    });
+                                }
+                                if response.from_cache {
+                                    ui.horizontal(|ui| {
+                                        ui.label("This response came from the response cache, the request wasn't sent");
+                                        if ui.button("Refresh").on_hover_text("Send it for real and cache the new response").clicked() {
+                                            let carry_over = CarryOver { refresh_cache: true, ..response.carry_over() };
+                                            match self.send_request(response.method.clone(), response.url.clone(), response.req_headers.clone(), response.req_body.clone(), response.proxy.clone(), carry_over) {
+                                                Ok(_) => self.ui_error = None,
+                                                Err(e) => self.ui_error = Some(format!("Error sending request: {}", e)),
+                                            }
+                                        }
+                                    });
+                                }
+                                if let Some(cache) = format::cache_info(&response.status, &response.headers) {
+                                    ui.horizontal(|ui| {
+                                        if cache.cached {
+                                            badge(ui, "cached", egui::Color32::from_rgb(90, 90, 160));
+                                        }
+                                        ui.label(cache.summary);
+                                    });
+                                }
+                                if let Some(conditional_headers) = format::conditional_headers(&response.req_headers, &response.headers)
+                                    && ui.button("Resend conditionally").on_hover_text("Resend with If-None-Match / If-Modified-Since from this response").clicked() {
+                                    match self.send_request(response.method.clone(), response.url.clone(), conditional_headers, response.req_body.clone(), response.proxy.clone(), response.carry_over()) {
+                                        Ok(_) => self.ui_error = None,
+                                        Err(e) => {
+                                            let error_msg = format!("Error sending request: {}", e);
+                                            eprintln!("{}", error_msg);
+                                            self.ui_error = Some(error_msg);
+                                        }
+                                    }
+                                }
+
+                                if ui.button("Export request").on_hover_text("Copy this request as JSON, for Import request JSON in New Request").clicked() {
+                                    let shared = SharedRequest {
+                                        method: response.method.clone(),
+                                        url: response.url.clone(),
+                                        headers: response.req_headers.clone(),
+                                        body: response.req_body.clone(),
+                                    };
+                                    match serde_json::to_string(&shared) {
+                                        Ok(json) => ui.ctx().copy_text(json),
+                                        Err(e) => self.ui_error = Some(format!("Error exporting request: {}", e)),
+                                    }
+                                }
+                                if ui.button("Save request").on_hover_text("Keep this request under Saved, where it can be pinned to the favorites bar").clicked() {
+                                    // A template keeps its path so the saved request follows the active environment
+                                    let url = response.template.as_ref().map(|template| template.url.clone()).unwrap_or_else(|| response.url.clone());
+                                    self.saved_requests.push(saved::SavedRequest {
+                                        name: format!("{} {}", response.method, url),
+                                        method: response.method.clone(),
+                                        url,
+                                        headers: response.req_headers.clone(),
+                                        body: response.req_body.clone(),
+                                        pinned: false,
+                                        ..Default::default()
+                                    });
+                                    self.show_saved = true;
+                                }
+
+                                // Edited in place so the note is saved with the history
+                                egui::CollapsingHeader::new(if response.note.is_empty() { "Note" } else { "📝 Note" })
+                                    .id_salt(("note", index))
+                                    .default_open(!response.note.is_empty())
+                                    .show(ui, |ui| {
+                                        let mut responses = self.request_responses.lock().unwrap();
+                                        if let Some(entry) = responses.iter_mut().find(|entry| entry.index == index) {
+                                            ui.add(
+                                                egui::TextEdit::multiline(&mut entry.note)
+                                                    .desired_width(f32::INFINITY)
+                                                    .desired_rows(2)
+                                                    .hint_text("e.g. the broken one from ticket #123")
+                                            );
+                                        }
+                                    });
+
+                                let results: Vec<bool> = response.assertions.iter()
+                                    .map(|assertion| assertion.passes(&response.status, &response.headers, &response.body, response.error.is_some()))
+                                    .collect();
+                                let passed = results.iter().filter(|passed| **passed).count();
+                                let title = if results.is_empty() { "Assertions".to_string() } else { format!("Assertions ({}/{} passed)", passed, results.len()) };
+                                egui::CollapsingHeader::new(title)
+                                    .id_salt(("assertions", index))
+                                    .default_open(!results.is_empty())
+                                    .show(ui, |ui| {
+                                        let mut responses = self.request_responses.lock().unwrap();
+                                        if let Some(entry) = responses.iter_mut().find(|entry| entry.index == index) {
+                                            assertion::show(ui, index, &mut entry.assertions, &results, &mut self.new_assertion_kind, &mut self.new_assertion_value);
+                                        }
+                                    });
+                            }
+                        });
+                        columns[1].add_space(10.0);
+
+                        // Edits start from the request as sent, leaving restores the formatted view
+                        let selected_index = *self.selected_response_index.lock().unwrap();
+                        let editing = selected_index.is_some() && self.editing_request == selected_index;
+                        if let Some(response) = selected_index.and_then(|index| self.get_response_by_index(index)) {
+                            columns[1].horizontal(|ui| {
+                                let mut edit = editing;
+                                if ui.toggle_value(&mut edit, "Edit").on_hover_text("Edit the headers and body below to resend them").changed() {
+                                    if edit {
+                                        self.editing_request = Some(response.index);
+                                        *self.show_requestheaders.lock().unwrap() = response.req_headers.clone();
+                                        *self.show_requestdetails.lock().unwrap() = response.req_body.clone();
+                                    }
+                                    else {
+                                        self.editing_request = None;
+                                        self.show_response(&response);
+                                    }
+                                }
+                                if editing && ui.button("Resend with edits").clicked() {
+                                    let headers = self.show_requestheaders.lock().unwrap().clone();
+                                    let body = self.show_requestdetails.lock().unwrap().clone();
+                                    match self.send_request(response.method.clone(), response.url.clone(), headers, body, response.proxy.clone(), response.carry_over()) {
+                                        Ok(_) => {
+                                            self.ui_error = None;
+                                            self.editing_request = None;
+                                        },
+                                        Err(e) => {
+                                            let error_msg = format!("Error sending request: {}", e);
+                                            eprintln!("{}", error_msg);
+                                            self.ui_error = Some(error_msg);
+                                        }
+                                    }
+                                }
+                            });
+                        }
+
+                        // Headers
+                        if editing || !self.show_requestheaders.lock().unwrap().is_empty() {
+                            columns[1].add(egui::Label::new("Headers"));
+                            egui::ScrollArea::vertical()
+                            .id_salt("req_headers")
+                            .max_height(150.0)
+                            .show(&mut columns[1], |ui| {
+                                ui.add(
+                                    egui::TextEdit::multiline(&mut *self.show_requestheaders.lock().unwrap())
+                                        .id_salt("req_headers_text")
+                                        .font(egui::FontId::proportional(self.ui_settings.text_size))
+                                        .desired_width(f32::INFINITY)
+                                        .desired_rows(3)
+                                        .interactive(editing)
+                                );
+                            });
+                            columns[1].add_space(10.0);
+                        }
+
+                        // Body
+                        if editing || !self.show_requestdetails.lock().unwrap().is_empty() {
+                            columns[1].add(egui::Label::new("Body"));
+                            egui::ScrollArea::vertical()
+                                .id_salt("req_body")
+                                .max_height(150.0)
+                                .show(&mut columns[1], |ui| {
+                                    ui.add(
+                                    egui::TextEdit::multiline(&mut *self.show_requestdetails.lock().unwrap())
+                                        .id_salt("req_body_text")
+                                        .font(egui::FontId::proportional(self.ui_settings.text_size))
+                                        .desired_width(f32::INFINITY)
+                                        .desired_rows(5)
+                                        .interactive(editing)
+                                );
+                            });
+                        }
                     }
 
                     /* Response */
                     columns[1].add_space(20.0);
-                    columns[1].heading("Response");
+                    columns[1].horizontal(|ui| {
+                        ui.heading("Response");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.add_space(20.0);
+                            if ui.button("A+").on_hover_text("Larger text (Ctrl+scroll)").clicked() {
+                                self.ui_settings.text_size = (self.ui_settings.text_size + 1.0).min(MAX_TEXT_SIZE);
+                            }
+                            if ui.button("Reset").clicked() {
+                                self.ui_settings.text_size = DEFAULT_TEXT_SIZE;
+                            }
+                            if ui.button("A-").on_hover_text("Smaller text (Ctrl+scroll)").clicked() {
+                                self.ui_settings.text_size = (self.ui_settings.text_size - 1.0).max(MIN_TEXT_SIZE);
+                            }
+                            ui.add_space(10.0);
+
+                            // Formatting only changes the display, resends use the stored body
+                            let previous_view = self.body_view.lock().unwrap().clone();
+                            let mut body_view = previous_view.clone();
+                            egui::ComboBox::from_id_salt("body_view_combo")
+                                .selected_text(body_view.as_str())
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut body_view, "Raw".to_string(), "Raw");
+                                    ui.selectable_value(&mut body_view, "Pretty".to_string(), "Pretty");
+                                    ui.selectable_value(&mut body_view, "Minified".to_string(), "Minified");
+                                    ui.selectable_value(&mut body_view, "Tree".to_string(), "Tree");
+                                });
+                            ui.label("View:");
+                            ui.add_space(10.0);
+
+                            let mut body_display_kb = *self.body_display_kb.lock().unwrap();
+                            ui.add(egui::DragValue::new(&mut body_display_kb).range(1..=100_000).suffix(" KB"))
+                                .on_hover_text("Bodies longer than this are truncated for display");
+                            ui.label("Limit:");
+                            if body_display_kb != *self.body_display_kb.lock().unwrap() {
+                                *self.body_display_kb.lock().unwrap() = body_display_kb;
+                                let selected_index = *self.selected_response_index.lock().unwrap();
+                                if let Some(response) = selected_index.and_then(|index| self.get_response_by_index(index)) {
+                                    self.show_response(&response);
+                                }
+                            }
+                            if body_view != previous_view {
+                                *self.body_view.lock().unwrap() = body_view;
+                                let selected_index = *self.selected_response_index.lock().unwrap();
+                                if let Some(response) = selected_index.and_then(|index| self.get_response_by_index(index)) {
+                                    self.show_response(&response);
+                                }
+                            }
+                        });
+                    });
                     columns[1].add_space(10.0);
 
+                    // Redirect chain, each hop as it came back before the next was requested
+                    if let Some(response) = self.selected_response_index.lock().unwrap().and_then(|index| responses.iter().find(|response| response.index == index))
+                        && !response.redirects.is_empty() {
+                        egui::CollapsingHeader::new(format!("Redirect chain ({} hops)", response.redirects.len()))
+                            .id_salt(("redirect_chain", response.index))
+                            .show(&mut columns[1], |ui| {
+                                for (position, hop) in response.redirects.iter().enumerate() {
+                                    egui::CollapsingHeader::new(format!("{}. {} {}", position + 1, hop.status, hop.url))
+                                        .id_salt(("redirect_hop", response.index, position))
+                                        .show(ui, |ui| {
+                                            let text = format!("{}\n\n{}", hop.headers.join("\n"), hop.body);
+                                            egui::ScrollArea::vertical().id_salt(("redirect_hop_scroll", response.index, position)).max_height(200.0).show(ui, |ui| {
+                                                ui.add(
+                                                    egui::TextEdit::multiline(&mut text.as_str())
+                                                        .font(egui::FontId::monospace(self.ui_settings.text_size))
+                                                        .desired_width(f32::INFINITY)
+                                                );
+                                            });
+                                        });
+                                }
+                            });
+                        columns[1].add_space(10.0);
+                    }
+
                     // Headers
                     if !self.show_responseheaders.lock().unwrap().is_empty() {
-                        columns[1].add(egui::Label::new("Headers"));
+                        columns[1].horizontal(|ui| {
+                            ui.label("Headers");
+                            if ui.small_button("Copy as JSON").on_hover_text("Repeated headers become arrays").clicked() {
+                                ui.ctx().copy_text(format::headers_json(&self.show_responseheaders.lock().unwrap()));
+                            }
+                        });
                         egui::ScrollArea::vertical()
                             .id_salt("res_headers")
                             .max_height(80.0)
@@ -305,6 +1773,7 @@ impl eframe::App for App {
                                 ui.add(
                                 egui::TextEdit::multiline(&mut *self.show_responseheaders.lock().unwrap())
                                     .id_salt("res_headers_text")
+                                    .font(egui::FontId::proportional(self.ui_settings.text_size))
                                     .desired_width(f32::INFINITY)
                                     .desired_rows(5)
                                     .interactive(false)
@@ -314,21 +1783,478 @@ impl eframe::App for App {
                     }
 
                     // Body
-                    columns[1].add(egui::Label::new("Body"));
+                    let selected_index = *self.selected_response_index.lock().unwrap();
+                    let selected_response = selected_index.and_then(|index| self.get_response_by_index(index));
+
+                    // Trailers
+                    if let Some(response) = &selected_response
+                        && !response.trailers.is_empty() {
+                        columns[1].label("Trailers").on_hover_text("Sent after the body, e.g. grpc-status in gRPC-web");
+                        egui::Frame::group(columns[1].style()).show(&mut columns[1], |ui| {
+                            ui.set_width(ui.available_width());
+                            for trailer in &response.trailers {
+                                ui.label(egui::RichText::new(trailer).monospace());
+                            }
+                        });
+                        columns[1].add_space(10.0);
+                    }
+                    if let Some(response) = &selected_response
+                        && self.ndjson_cache.as_ref().map(|(index, _)| *index) != Some(response.index) {
+                        let content_type = format::header_value(&response.headers.join("\n"), "Content-Type").unwrap_or_default();
+                        self.ndjson_cache = Some((response.index, format::ndjson_lines(&content_type, &response.body)));
+                    }
+                    let ndjson_lines = match (&selected_response, &self.ndjson_cache) {
+                        (Some(response), Some((index, lines))) if response.index == *index && response.error.is_none() => lines.clone(),
+                        _ => None,
+                    };
+                    let tree_view = *self.body_view.lock().unwrap() == "Tree";
+                    if tree_view
+                        && let Some(response) = &selected_response
+                        && self.json_tree_cache.as_ref().map(|(index, _)| *index) != Some(response.index) {
+                        self.json_tree_cache = Some((response.index, serde_json::from_str(&response.body).ok()));
+                    }
+                    // Taken out for the frame so the tree can borrow it while the rest of self is in use
+                    let json_tree = match (&selected_response, self.json_tree_cache.take()) {
+                        (Some(response), Some((index, Some(value)))) if tree_view && response.index == index && response.error.is_none() => Some((index, value)),
+                        (_, cache) => {
+                            self.json_tree_cache = cache;
+                            None
+                        },
+                    };
+                    let rendered_as = |kind: &str| selected_response.as_ref().is_some_and(|response| {
+                        let mode = self.render_mode.lock().unwrap().clone();
+                        response.error.is_none() && match self.sniff_cache {
+                            _ if mode != "Auto" => mode == kind,
+                            Some((index, sniffed)) => index == response.index && sniffed == kind,
+                            None => false,
+                        }
+                    });
+                    let markdown = rendered_as("Markdown");
+                    let form = rendered_as("Form");
+                    columns[1].horizontal(|ui| {
+                        ui.label("Body");
+
+                        // Overrides Content-Type detection, e.g. text sent as application/octet-stream
+                        if let Some(response) = &selected_response {
+                            let content_type = format::header_value(&response.headers.join("\n"), "Content-Type").unwrap_or_default();
+                            ui.label(egui::RichText::new(if content_type.is_empty() { "no Content-Type" } else { content_type.as_str() }).strong());
+                            let previous_mode = self.render_mode.lock().unwrap().clone();
+                            let mut mode = previous_mode.clone();
+                            // Sniffing parses the body, so only once per response
+                            let kind = match self.sniff_cache {
+                                Some((index, kind)) if index == response.index => kind,
+                                _ => {
+                                    let kind = format::sniff_kind(&content_type, &response.body);
+                                    self.sniff_cache = Some((response.index, kind));
+                                    kind
+                                },
+                            };
+                            let auto_label = format!("Auto ({})", kind);
+                            egui::ComboBox::from_id_salt("render_mode_combo")
+                                .selected_text(if mode == "Auto" { auto_label.as_str() } else { mode.as_str() })
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut mode, "Auto".to_string(), auto_label.as_str());
+                                    for kind in ["JSON", "XML", "HTML", "Markdown", "Form", "Text", "Hex"] {
+                                        ui.selectable_value(&mut mode, kind.to_string(), kind);
+                                    }
+                                });
+                            if mode != previous_mode {
+                                *self.render_mode.lock().unwrap() = mode;
+                                self.show_response(response);
+                            }
+                        }
+
+                        let body = match selected_response.as_ref() {
+                            Some(response) => response.body.clone(),
+                            None => self.show_responsedetails.lock().unwrap().clone(),
+                        };
+                        ui.menu_button("Copy as", |ui| {
+                            let body_is_json = format::is_json(&body);
+                            if ui.button("Raw").clicked() {
+                                ui.ctx().copy_text(body.clone());
+                                ui.close_menu();
+                            }
+                            if ui.add_enabled(body_is_json, egui::Button::new("Pretty JSON")).clicked() {
+                                ui.ctx().copy_text(format::pretty_json(&body).unwrap_or_default());
+                                ui.close_menu();
+                            }
+                            if ui.add_enabled(body_is_json, egui::Button::new("Minified JSON")).clicked() {
+                                ui.ctx().copy_text(format::minify_json(&body).unwrap_or_default());
+                                ui.close_menu();
+                            }
+                            // Encodes the bytes as received, body holds a lossy decoding of binary responses
+                            if ui.button("Base64").clicked() {
+                                let bytes = selected_response.as_ref().map(|response| response.body_bytes()).unwrap_or_else(|| body.as_bytes().to_vec());
+                                ui.ctx().copy_text(base64::engine::general_purpose::STANDARD.encode(bytes));
+                                ui.close_menu();
+                            }
+                        });
+                        if ndjson_lines.is_some() {
+                            ui.checkbox(&mut self.ndjson_raw, "Raw");
+                        }
+                        if markdown || form {
+                            ui.checkbox(&mut self.preview_source, "Source");
+                        }
+                        if form && !self.preview_source {
+                            ui.checkbox(&mut self.decode_display, "Decode")
+                                .on_hover_text("Off shows the pairs as sent, useful for spotting double-encoding");
+                        }
+
+                        // Full body stays stored for copying, only the display is cut short
+                        if let Some(response) = &selected_response
+                            && !*self.show_full_body.lock().unwrap()
+                            && response.body.len() > *self.body_display_kb.lock().unwrap() * 1024
+                            && ui.button(format!("Show full body ({} KB)", response.body.len() / 1024)).clicked() {
+                            *self.show_full_body.lock().unwrap() = true;
+                            self.show_response(response);
+                        }
+
+                        // Display only, copying and resending always use the stored bodies
+                        let long_lines = *self.long_lines.lock().unwrap();
+                        if long_lines > 0 {
+                            let previous_mode = self.ui_settings.long_line_mode.clone();
+                            let mut line_mode = previous_mode.clone();
+                            ui.label(format!("{} long line{}:", long_lines, if long_lines == 1 { "" } else { "s" }))
+                                .on_hover_text(format!("Lines over {} bytes slow down rendering", format::LONG_LINE_THRESHOLD));
+                            egui::ComboBox::from_id_salt("long_line_combo")
+                                .selected_text(line_mode.as_str())
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut line_mode, "Wrap".to_string(), "Wrap");
+                                    ui.selectable_value(&mut line_mode, "Collapse".to_string(), "Collapse");
+                                    ui.selectable_value(&mut line_mode, "Off".to_string(), "Off");
+                                });
+                            if line_mode != previous_mode {
+                                self.ui_settings.long_line_mode = line_mode;
+                                if let Some(response) = &selected_response {
+                                    self.show_response(response);
+                                }
+                            }
+                        }
+                    });
+
+                    // Find in body, Ctrl+F while the pointer is over the response
+                    if columns[1].ui_contains_pointer() && columns[1].input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::F)) {
+                        self.find_open = true;
+                        self.set_focus = "find".to_string();
+                    }
+                    let find_matches = if self.find_open {
+                        let body = self.show_responsedetails.lock().unwrap();
+                        match &self.find_cache {
+                            Some((index, query, length, matches)) if *index == selected_index && *query == self.find_query && *length == body.len() => matches.clone(),
+                            _ => {
+                                let matches = format::find_matches(&body, &self.find_query);
+                                self.find_cache = Some((selected_index, self.find_query.clone(), body.len(), matches.clone()));
+                                matches
+                            },
+                        }
+                    }
+                    else {
+                        Vec::new()
+                    };
+                    if self.find_open {
+                        columns[1].horizontal(|ui| {
+                            ui.label("Find:");
+                            let input = ui.add(egui::TextEdit::singleline(&mut self.find_query).desired_width(200.0));
+                            if self.set_focus == "find" {
+                                input.request_focus();
+                                self.set_focus.clear();
+                            }
+                            if input.changed() {
+                                self.find_current = 0;
+                                self.find_scroll = true;
+                            }
+                            let mut step = 0;
+                            if input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                step = if ui.input(|i| i.modifiers.shift) { -1 } else { 1 };
+                                input.request_focus();
+                            }
+                            if ui.add_enabled(!find_matches.is_empty(), egui::Button::new("Previous")).clicked() {
+                                step = -1;
+                            }
+                            if ui.add_enabled(!find_matches.is_empty(), egui::Button::new("Next")).clicked() {
+                                step = 1;
+                            }
+                            if step != 0 && !find_matches.is_empty() {
+                                self.find_current = (self.find_current as isize + step).rem_euclid(find_matches.len() as isize) as usize;
+                                self.find_scroll = true;
+                            }
+                            if find_matches.is_empty() {
+                                ui.label(if self.find_query.is_empty() { "" } else { "No matches" });
+                            }
+                            else {
+                                ui.label(format!("{} of {}", self.find_current.min(find_matches.len() - 1) + 1, find_matches.len()));
+                            }
+                            if ui.button("Close").clicked() || (input.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Escape))) {
+                                self.find_open = false;
+                            }
+                        });
+                    }
+                    if let Some((index, value)) = &json_tree {
+                        self.tree_search.bar(&mut columns[1], *index, value);
+                    }
+                    // With the request collapsed the body takes the rest of the column, leaving room for the sections under it
+                    let body_height = if self.request_collapsed { (columns[1].available_height() - 150.0).max(350.0) } else { 350.0 };
                     egui::ScrollArea::vertical()
                         .id_salt("res_body")
-                        .max_height(350.0)
+                        .max_height(body_height)
                         .show(&mut columns[1], |ui| {
-                            ui.add(
-                            egui::TextEdit::multiline(&mut *self.show_responsedetails.lock().unwrap())
-                                .id_salt("res_body_text")
-                                .desired_width(f32::INFINITY)
-                                .desired_rows(10)
-                                .interactive(false)
-                        );
+                            if let Some((_, value)) = &json_tree {
+                                json_tree::show(ui, value, &mut self.tree_search);
+                            }
+                            else {
+                                match &ndjson_lines {
+                                    Some(lines) if !self.ndjson_raw => {
+                                        for (line_number, line) in lines.iter().enumerate() {
+                                            let preview: String = line.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(80).collect();
+                                            egui::CollapsingHeader::new(format!("{}: {}", line_number + 1, preview))
+                                                .id_salt(("ndjson_line", line_number))
+                                                .show(ui, |ui| {
+                                                    ui.add(
+                                                    egui::TextEdit::multiline(&mut line.as_str())
+                                                        .font(egui::FontId::proportional(self.ui_settings.text_size))
+                                                        .desired_width(f32::INFINITY)
+                                                );
+                                            });
+                                        }
+                                    },
+                                    _ if markdown && !self.preview_source => {
+                                        markdown::show(ui, &self.show_responsedetails.lock().unwrap(), self.ui_settings.text_size);
+                                    },
+                                    _ if form && !self.preview_source => {
+                                        let body = self.show_responsedetails.lock().unwrap().clone();
+                                        form_grid(ui, "response_form_grid", if self.decode_display { format::parse_form(&body) } else { format::split_form(&body) });
+                                    },
+                                    _ => {
+                                        let current = find_matches.get(self.find_current).cloned();
+                                        let font = egui::FontId::proportional(self.ui_settings.text_size);
+                                        let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                                            let mut job = egui::text::LayoutJob::default();
+                                            let plain = egui::TextFormat::simple(font.clone(), ui.visuals().text_color());
+                                            let highlight = |background| egui::TextFormat {
+                                                background,
+                                                ..egui::TextFormat::simple(font.clone(), egui::Color32::BLACK)
+                                            };
+                                            let mut position = 0;
+                                            // Ranges are byte offsets into this exact text, skip them if it changed under us
+                                            for range in find_matches.iter().filter(|range| text.is_char_boundary(range.start) && range.end <= text.len() && text.is_char_boundary(range.end)) {
+                                                job.append(&text[position..range.start], 0.0, plain.clone());
+                                                let background = if Some(range) == current.as_ref() { egui::Color32::from_rgb(255, 150, 50) } else { egui::Color32::from_rgb(255, 230, 120) };
+                                                job.append(&text[range.clone()], 0.0, highlight(background));
+                                                position = range.end;
+                                            }
+                                            job.append(&text[position..], 0.0, plain);
+                                            job.wrap.max_width = wrap_width;
+                                            ui.fonts(|fonts| fonts.layout_job(job))
+                                        };
+                                        let output = egui::TextEdit::multiline(&mut *self.show_responsedetails.lock().unwrap())
+                                            .id_salt("res_body_text")
+                                            .font(egui::FontId::proportional(self.ui_settings.text_size))
+                                            .desired_width(f32::INFINITY)
+                                            .desired_rows(10)
+                                            .interactive(false)
+                                            .layouter(&mut layouter)
+                                            .show(ui);
+                                        if self.find_scroll && let Some(range) = current {
+                                            let character = self.show_responsedetails.lock().unwrap()[..range.start].chars().count();
+                                            let rect = output.galley.pos_from_ccursor(egui::text::CCursor::new(character));
+                                            ui.scroll_to_rect(rect.translate(output.galley_pos.to_vec2()), Some(egui::Align::Center));
+                                        }
+                                        self.find_scroll = false;
+                                    },
+                                }
+                            }
                     });
+                    if let Some((index, value)) = json_tree {
+                        self.json_tree_cache = Some((index, Some(value)));
+                    }
+
+                    // Hex inspector, on the bytes as received rather than the decoded text
+                    if let Some(response) = &selected_response
+                        && response.error.is_none() {
+                        egui::CollapsingHeader::new("Hex inspector")
+                            .id_salt("hex_inspector")
+                            .show(&mut columns[1], |ui| {
+                                self.hex_inspector.show(ui, response.index, || response.body_bytes());
+                            });
+                    }
+
+                    // Inferred from this one response, so optional keys only show up as such across array items
+                    if let Some(response) = &selected_response
+                        && response.error.is_none() {
+                        if self.schema_cache.as_ref().map(|(index, _)| *index) != Some(response.index) {
+                            self.schema_cache = Some((response.index, format::infer_schema(&response.body)));
+                        }
+                        if let Some((_, Some(schema))) = &self.schema_cache {
+                            egui::CollapsingHeader::new("Schema")
+                                .id_salt("schema")
+                                .show(&mut columns[1], |ui| {
+                                    if ui.button("Copy schema").clicked() {
+                                        ui.ctx().copy_text(schema.clone());
+                                    }
+                                    egui::ScrollArea::vertical().id_salt("schema_scroll").max_height(300.0).show(ui, |ui| {
+                                        ui.add(
+                                            egui::TextEdit::multiline(&mut schema.as_str())
+                                                .font(egui::FontId::monospace(self.ui_settings.text_size))
+                                                .desired_width(f32::INFINITY)
+                                        );
+                                    });
+                                });
+                        }
+                    }
+                    let zoom = columns[1].input(|i| i.zoom_delta());
+                    if columns[1].ui_contains_pointer() && zoom != 1.0 {
+                        self.ui_settings.text_size = (self.ui_settings.text_size * zoom).clamp(MIN_TEXT_SIZE, MAX_TEXT_SIZE);
+                    }
+
+                    // Watches
+                    columns[1].add_space(10.0);
+                    let mut watches_changed = false;
+                    egui::CollapsingHeader::new(format!("Watches ({})", self.watches.len()))
+                        .id_salt("watches")
+                        .show(&mut columns[1], |ui| {
+                            let values = selected_response.as_ref().and_then(|response| self.watch_values(response));
+                            let mut remove = None;
+                            egui::Grid::new("watches_grid").striped(true).show(ui, |ui| {
+                                for (position, watch) in self.watches.iter().enumerate() {
+                                    ui.label(watch);
+                                    ui.label(values.as_ref().map(|values| values[position].as_str()).unwrap_or("-"));
+                                    if ui.small_button("Remove").clicked() {
+                                        remove = Some(position);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if let Some(position) = remove {
+                                self.watches.remove(position);
+                                watches_changed = true;
+                            }
+                            ui.horizontal(|ui| {
+                                let input = ui.add(egui::TextEdit::singleline(&mut self.new_watch).hint_text("data.items[0].id"));
+                                let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                if (ui.button("Add").clicked() || submitted) && !self.new_watch.trim().is_empty() {
+                                    self.watches.push(self.new_watch.trim().to_string());
+                                    self.new_watch.clear();
+                                    watches_changed = true;
+                                }
+                            });
+                        });
+                    if watches_changed {
+                        self.watch_cache.clear();
+                    }
+
+                    // Raw exchange, one block for pasting into bug reports
+                    if let Some(index) = *self.selected_response_index.lock().unwrap()
+                        && let Some(response) = self.get_response_by_index(index)
+                        && !response.raw_exchange.is_empty() {
+                        columns[1].add_space(10.0);
+                        egui::CollapsingHeader::new("Raw Exchange")
+                            .id_salt("raw_exchange")
+                            .show(&mut columns[1], |ui| {
+                                let display_limit = *self.body_display_kb.lock().unwrap() * 1024;
+                                let text = format!("{}\n\n{}", response.raw_exchange, format::truncate_for_display(&response.body, display_limit));
+                                if ui.button("Copy raw exchange").clicked() {
+                                    ui.ctx().copy_text(format!("{}\n\n{}", response.raw_exchange, response.body));
+                                }
+                                egui::ScrollArea::both()
+                                    .id_salt("raw_exchange_scroll")
+                                    .max_height(300.0)
+                                    .show(ui, |ui| {
+                                        ui.add(
+                                        egui::TextEdit::multiline(&mut text.as_str())
+                                            .id_salt("raw_exchange_text")
+                                            .font(egui::FontId::monospace(self.ui_settings.text_size))
+                                            .desired_width(f32::INFINITY)
+                                            .desired_rows(5)
+                                    );
+                                });
+                            });
+                    }
+
+                    // The request's bytes for servers that care about exact framing, rebuilt in HTTP/1.1 form
+                    if let Some(index) = *self.selected_response_index.lock().unwrap()
+                        && let Some(response) = self.get_response_by_index(index)
+                        && !response.raw_exchange.is_empty() {
+                        columns[1].add_space(10.0);
+                        egui::CollapsingHeader::new("Request bytes (hex, rebuilt as HTTP/1.1)")
+                            .id_salt("request_hex_inspector")
+                            .show(&mut columns[1], |ui| {
+                                ui.small("Rebuilt from the request's headers and body, with secret header values masked. Only accurate for HTTP/1.1");
+                                // The response line is the first of the last block, after any blank lines in the body
+                                if response.raw_exchange.rsplit("\n\n").next().is_some_and(|head| head.starts_with("HTTP/2")) {
+                                    ui.colored_label(egui::Color32::from_rgb(220, 140, 40), "This went over HTTP/2, which sends the same headers as binary frames instead");
+                                }
+                                if !response.body_file.is_empty() {
+                                    ui.label(format!("Headers only, the body was streamed from {}", response.body_file));
+                                }
+                                self.request_hex_inspector.show(ui, response.index, || response.sent_bytes());
+                            });
+                    }
+
+                    // Diagnostics
+                    if !self.show_diagnostics.lock().unwrap().is_empty() {
+                        columns[1].add_space(10.0);
+                        egui::CollapsingHeader::new("Diagnostics")
+                            .id_salt("diagnostics")
+                            .show(&mut columns[1], |ui| {
+                                let trace = self.show_diagnostics.lock().unwrap().clone();
+                                ui.horizontal(|ui| {
+                                    if ui.button("Copy trace").clicked() {
+                                        ui.ctx().copy_text(trace.clone());
+                                    }
+                                    ui.checkbox(&mut self.trace_line_numbers, "Line numbers");
+                                });
+
+                                // Identical client failures are summarised, their attempts folded away below it
+                                let (summary, client_detail) = match trace.split_once(request::CLIENT_DETAIL_MARKER) {
+                                    Some((summary, detail)) => (summary, Some(detail)),
+                                    None => (trace.as_str(), None),
+                                };
+                                let width = trace.lines().count().to_string().len();
+                                let numbered = |text: &str, first: usize| if self.trace_line_numbers {
+                                    text.lines().enumerate()
+                                        .map(|(number, line)| format!("{:>width$}  {}", first + number, line, width = width))
+                                        .collect::<Vec<String>>()
+                                        .join("\n")
+                                }
+                                else {
+                                    text.to_string()
+                                };
+                                let text = numbered(summary, 1);
+                                let client_detail = client_detail.map(|detail| numbered(detail, summary.lines().count() + 2));
+                                // Scrolls on its own so a long trace doesn't push the rest of the panel around
+                                egui::ScrollArea::both()
+                                    .id_salt("diagnostics_scroll")
+                                    .max_height(300.0)
+                                    .show(ui, |ui| {
+                                        // A &str buffer keeps the text selectable while read-only
+                                        ui.add(
+                                        egui::TextEdit::multiline(&mut text.as_str())
+                                            .id_salt("diagnostics_text")
+                                            .font(egui::FontId::monospace(self.ui_settings.text_size))
+                                            .desired_width(f32::INFINITY)
+                                            .desired_rows(5)
+                                    );
+                                        if let Some(detail) = client_detail {
+                                            egui::CollapsingHeader::new("Per-client detail")
+                                                .id_salt("diagnostics_client_detail")
+                                                .show(ui, |ui| {
+                                                    ui.add(
+                                                        egui::TextEdit::multiline(&mut detail.as_str())
+                                                            .id_salt("diagnostics_client_detail_text")
+                                                            .font(egui::FontId::monospace(self.ui_settings.text_size))
+                                                            .desired_width(f32::INFINITY)
+                                                    );
+                                                });
+                                        }
+                                });
+                            });
+                    }
                 }
             });
+            // The columns borrow self, so a drag is written back after them
+            if split != split_before {
+                self.ui_settings.split = split;
+            }
 
         });
 
@@ -336,34 +2262,119 @@ impl eframe::App for App {
         if self.show_newrequest {
             egui::Window::new("New Request")
                 .collapsible(false)
-                .resizable(false)
+                .resizable(true)
+                .vscroll(true)
+                .default_size(NEW_REQUEST_SIZE)
                 .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
                 .show(ctx, |ui| {
+                    // The body gets a share of the window's height, so resizing makes room for big payloads
+                    let body_rows = (ui.clip_rect().height() * 0.4 / ui.text_style_height(&egui::TextStyle::Body)).max(10.0) as usize;
                     ui.add_space(10.0);
 
                     ui.vertical_centered(|ui| {
 
+                        /* Import */
+                        egui::CollapsingHeader::new("Import request JSON")
+                            .id_salt("import_request")
+                            .show(ui, |ui| {
+                                ui.add(
+                                    egui::TextEdit::multiline(&mut self.import_json)
+                                        .desired_width(f32::INFINITY)
+                                        .desired_rows(3)
+                                        .hint_text("{\"method\":\"GET\",\"url\":\"http://...\"}")
+                                );
+                                if ui.button("Import").clicked() {
+                                    match serde_json::from_str::<SharedRequest>(&self.import_json) {
+                                        Ok(shared) => {
+                                            self.load_draft(&shared.method, &shared.url, &shared.headers, &shared.body);
+                                            self.import_json.clear();
+                                            self.import_error = None;
+                                        },
+                                        Err(e) => self.import_error = Some(format!("Invalid request JSON: {}", e)),
+                                    }
+                                }
+                                if let Some(error) = &self.import_error {
+                                    ui.colored_label(egui::Color32::RED, error);
+                                }
+                            });
+                        egui::CollapsingHeader::new("Import raw HTTP")
+                            .id_salt("import_raw")
+                            .show(ui, |ui| {
+                                ui.add(
+                                    egui::TextEdit::multiline(&mut self.import_raw)
+                                        .font(egui::TextStyle::Monospace)
+                                        .desired_width(f32::INFINITY)
+                                        .desired_rows(5)
+                                        .hint_text("POST /api/items HTTP/1.1\nHost: example.com\nContent-Type: application/json\n\n{\"name\":\"x\"}")
+                                );
+                                if ui.button("Import").on_hover_text("Host becomes part of the URL, Content-Length is recalculated on send").clicked() {
+                                    match request::parse_raw_http(&self.import_raw) {
+                                        Ok((method, url, headers, body)) => {
+                                            self.load_draft(&method, &url, &headers, &body);
+                                            self.import_raw.clear();
+                                            self.import_raw_error = None;
+                                        },
+                                        Err(e) => self.import_raw_error = Some(format!("Invalid raw request: {}", e)),
+                                    }
+                                }
+                                if let Some(error) = &self.import_raw_error {
+                                    ui.colored_label(egui::Color32::RED, error);
+                                }
+                            });
+                        ui.add_space(10.0);
+
                         /* Method Selector */
                         ui.horizontal(|ui| {
                             ui.label("Method:");
                             let mut request_type = self.request_type.lock().unwrap();
-                            egui::ComboBox::from_id_salt("request_type_combo")
+                            let combo = egui::ComboBox::from_id_salt("request_type_combo")
                                 .selected_text(request_type.as_str())
                                 .show_ui(ui, |ui| {
-                                    ui.selectable_value(&mut *request_type, "GET".to_string(), "GET");
-                                    ui.selectable_value(&mut *request_type, "POST".to_string(), "POST");
-                                    ui.selectable_value(&mut *request_type, "PUT".to_string(), "PUT");
-                                    ui.selectable_value(&mut *request_type, "PATCH".to_string(), "PATCH");
-                                    ui.selectable_value(&mut *request_type, "DELETE".to_string(), "DELETE");
+                                    for method in METHODS {
+                                        ui.selectable_value(&mut *request_type, method.to_string(), method);
+                                    }
                                 });
+                            let combo = combo.response.on_hover_text("Ctrl+M / Ctrl+Shift+M, or up/down while focused");
+
+                            // Arrows step the method instead of moving focus while the combo is focused
+                            let mut step = 0;
+                            if combo.has_focus() {
+                                ui.memory_mut(|memory| memory.set_focus_lock_filter(combo.id, egui::EventFilter { vertical_arrows: true, ..Default::default() }));
+                                if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
+                                    step = 1;
+                                }
+                                if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
+                                    step = -1;
+                                }
+                            }
+                            if ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::M)) {
+                                step = -1;
+                            }
+                            else if ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::M)) {
+                                step = 1;
+                            }
+                            if step != 0 {
+                                let position = METHODS.iter().position(|method| *method == request_type.as_str()).unwrap_or(0) as isize;
+                                *request_type = METHODS[(position + step).rem_euclid(METHODS.len() as isize) as usize].to_string();
+                            }
+                            if *request_type == "Custom..." {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut *self.custom_method.lock().unwrap())
+                                        .desired_width(120.0)
+                                        .hint_text("PROPFIND")
+                                );
+                            }
                         });
+                        if let Err(e) = request::parse_method(&self.selected_method()) {
+                            ui.colored_label(egui::Color32::RED, e);
+                        }
 
                         /* URL Input */
                         ui.horizontal(|ui| {
                             ui.label("URL:");
                             let requesturl = ui.add(
                                 egui::TextEdit::singleline(&mut *self.request_url.lock().unwrap())
-                                    .desired_width(330.0)
+                                    .desired_width(ui.available_width())
                                     .hint_text("api.example.com/endpoint")
                             );
                             if self.show_newrequest && self.set_focus == "newrequest" {
@@ -374,17 +2385,18 @@ impl eframe::App for App {
                             }
 
                             if requesturl.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                                let send_enabled = !self.request_url.lock().unwrap().is_empty() && !is_loading;
+                                let send_enabled = !self.request_url.lock().unwrap().is_empty() && !is_loading && request::parse_method(&self.selected_method()).is_ok() && self.resolved_url().is_ok_and(|url| request::apply_default_scheme(&url, &self.default_scheme).is_ok());
                                 if send_enabled {
                                     self.show_newrequest = false;    
-                                    match self.send_request(self.request_type.lock().unwrap().clone(), self.request_url.lock().unwrap().clone(), self.request_headers.lock().unwrap().clone(), self.request_body.lock().unwrap().clone()) {
+                                    match self.send_request(self.selected_method(), self.resolved_url().unwrap_or_default(), self.request_headers.lock().unwrap().clone(), self.request_body.lock().unwrap().clone(), self.request_proxy.lock().unwrap().clone(), self.draft_carry_over()) {
                                         Ok(_) => {
                                             self.ui_error = None;
                                             *self.show_requestheaders.lock().unwrap() = String::new();
                                             *self.show_requestdetails.lock().unwrap() = String::new();
                                             *self.show_responsedetails.lock().unwrap() = String::new();
                                             *self.show_responseheaders.lock().unwrap() = String::new();
-                                            self.selected_response_index = None;
+                                            *self.show_diagnostics.lock().unwrap() = String::new();
+                                            *self.selected_response_index.lock().unwrap() = None;
                                         },
                                         Err(e) => {
                                             let error_msg = format!("Error sending request: {}", e);
@@ -396,42 +2408,348 @@ impl eframe::App for App {
                                 }
                             }
                         });
+                        ui.horizontal(|ui| {
+                            ui.label("Without a scheme:");
+                            egui::ComboBox::from_id_salt("default_scheme_combo")
+                                .selected_text(self.default_scheme.as_str())
+                                .show_ui(ui, |ui| {
+                                    for scheme in request::DEFAULT_SCHEMES {
+                                        ui.selectable_value(&mut self.default_scheme, scheme.to_string(), scheme);
+                                    }
+                                })
+                                .response.on_hover_text("What a URL typed without http:// or https:// is sent as. Require refuses to send it");
+                            if let Ok(url) = self.resolved_url()
+                                && !url.is_empty()
+                                && let Err(e) = request::apply_default_scheme(&url, &self.default_scheme) {
+                                ui.colored_label(egui::Color32::RED, e);
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.https_only, "HTTPS only")
+                                .on_hover_text("Refuse to send plain HTTP, follow no redirect to it and never fall back to the Permissive client, so an invalid certificate is an error");
+                            if self.https_only {
+                                https_only_badge(ui);
+                                if let Ok(url) = self.resolved_url()
+                                    && let Ok(url) = request::apply_default_scheme(&url, &self.default_scheme)
+                                    && request::normalize_url(&url).starts_with("http://") {
+                                    ui.colored_label(egui::Color32::RED, "Plain HTTP, this will be refused");
+                                }
+                            }
+                        });
+
+                        /* Environment */
+                        ui.horizontal(|ui| {
+                            ui.label("Environment:");
+                            egui::ComboBox::from_id_salt("environment_combo")
+                                .selected_text(if self.active_environment.is_empty() { "None" } else { self.active_environment.as_str() })
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut self.active_environment, String::new(), "None");
+                                    for environment in &self.environments {
+                                        ui.selectable_value(&mut self.active_environment, environment.name.clone(), &environment.name);
+                                    }
+                                });
+                            if ui.button("Manage").clicked() {
+                                self.show_environments = true;
+                            }
+                        });
+                        if environment::find(&self.environments, &self.active_environment).is_some_and(|environment| !environment.base_url.trim().is_empty() || self.request_url.lock().unwrap().contains("{{"))
+                            && !self.request_url.lock().unwrap().is_empty() {
+                            match self.resolved_url() {
+                                Ok(url) => { ui.label(format!("Resolved: {}", request::redact_credentials(&request::normalize_url(&request::apply_default_scheme(&url, &self.default_scheme).unwrap_or(url))))); },
+                                Err(e) => { ui.colored_label(egui::Color32::RED, e); },
+                            }
+                        }
+
+                        /* DNS Resolver */
+                        ui.horizontal(|ui| {
+                            ui.label("DNS:");
+                            let mut dns_provider = self.dns_provider.lock().unwrap();
+                            egui::ComboBox::from_id_salt("dns_provider_combo")
+                                .selected_text(dns_provider.as_str())
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut *dns_provider, "System".to_string(), "System");
+                                    ui.selectable_value(&mut *dns_provider, "Cloudflare".to_string(), "Cloudflare (DoH)");
+                                    ui.selectable_value(&mut *dns_provider, "Google".to_string(), "Google (DoH)");
+                                });
+                        });
+
+                        /* Proxy */
+                        ui.horizontal(|ui| {
+                            ui.label("Proxy:");
+                            ui.add(egui::TextEdit::singleline(&mut *self.request_proxy.lock().unwrap()).desired_width(250.0).hint_text("localhost:8080, blank for direct"))
+                                .on_hover_text("Only this request goes through the proxy, resending it reuses the proxy");
+                        });
+
+                        /* TLS Range */
+                        ui.horizontal(|ui| {
+                            ui.label("TLS min:");
+                            tls_combo(ui, "tls_min_combo", &mut self.tls_min.lock().unwrap());
+                            ui.label("max:");
+                            tls_combo(ui, "tls_max_combo", &mut self.tls_max.lock().unwrap());
+                        }).response.on_hover_text("Any other than Any/Any tries only a client limited to this range");
+                        ui.horizontal(|ui| {
+                            ui.label("Start at client:");
+                            let mut first_client = self.first_client.lock().unwrap();
+                            egui::ComboBox::from_id_salt("first_client_combo")
+                                .selected_text(first_client.as_str())
+                                .show_ui(ui, |ui| {
+                                    for tier in request::CLIENT_TIERS {
+                                        ui.selectable_value(&mut *first_client, tier.to_string(), tier);
+                                    }
+                                });
+                        }).response.on_hover_text("Skips the clients before this one, e.g. Legacy TLS for a server known to need it. Resends start at the same client");
+                        ui.checkbox(&mut self.capture_redirects, "Keep redirect bodies")
+                            .on_hover_text("Follow redirects one at a time and keep each 3xx response's status, headers and body, shown as a chain in the details");
+                        ui.checkbox(&mut self.allow_bodyless_body, "Allow body on GET/DELETE")
+                            .on_hover_text("Non-standard: HTTP gives a body on GET, HEAD, DELETE, OPTIONS or TRACE no meaning and some servers and proxies reject or ignore it. Off leaves the body out. Resends keep the setting they were sent with");
+
+                        /* Connection Pool */
+                        ui.horizontal(|ui| {
+                            ui.label("Idle connections per host:");
+                            ui.add(egui::DragValue::new(&mut *self.pool_max_idle.lock().unwrap()).range(0..=256))
+                                .on_hover_text("Keep-alive connections each client keeps open per host, 0 disables reuse");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Probe retries:");
+                            ui.add(egui::DragValue::new(&mut *self.probe_retries.lock().unwrap()).range(0..=10));
+                            ui.add(egui::DragValue::new(&mut *self.probe_retry_delay_ms.lock().unwrap()).range(0..=10000).suffix(" ms"))
+                                .on_hover_text("Delay between attempts, only timeouts and refused connections are retried");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Max response size:");
+                            ui.add(egui::DragValue::new(&mut *self.max_response_mb.lock().unwrap()).range(0..=100_000).suffix(" MB"))
+                                .on_hover_text("Reading stops here and keeps what arrived, 0 for no limit");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.cache_enabled, "Cache responses for")
+                                .on_hover_text("An identical request (method, URL, headers, body and auth) within this time is answered from history without sending");
+                            ui.add_enabled(self.cache_enabled, egui::DragValue::new(&mut self.cache_ttl_secs).range(1..=86_400).suffix(" s"));
+                            ui.label("up to");
+                            ui.add_enabled(self.cache_enabled, egui::DragValue::new(&mut self.cache_max_entries).range(1..=1000).suffix(" entries"));
+                            let cached = self.response_cache.lock().unwrap().len();
+                            if ui.add_enabled(cached > 0, egui::Button::new(format!("Clear ({})", cached))).clicked() {
+                                self.response_cache.lock().unwrap().clear();
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Network conditions:");
+                            ui.add(egui::DragValue::new(&mut *self.send_delay_ms.lock().unwrap()).range(0..=60_000).prefix("delay ").suffix(" ms"))
+                                .on_hover_text("Wait this long before sending, to simulate latency");
+                            ui.add(egui::DragValue::new(&mut *self.throttle_bytes_per_sec.lock().unwrap()).range(0..=100_000_000).speed(100).prefix("throttle ").suffix(" B/s"))
+                                .on_hover_text("Read the response body no faster than this, 0 for full speed. The 30 second timeout still applies");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Extra root CA:");
+                            ui.add(egui::TextEdit::singleline(&mut self.root_ca_file).desired_width(250.0).hint_text("/path/to/private-ca.pem"))
+                                .on_hover_text("PEM certificates trusted on top of the OS trust store, for servers with a private CA");
+                        });
+                        let root_ca_file = self.root_ca_file.trim();
+                        if !root_ca_file.is_empty() {
+                            if self.root_ca_check.as_ref().is_none_or(|(checked, _)| checked != root_ca_file) {
+                                let check = request::root_certificates(root_ca_file).map(|certificates| certificates.len());
+                                self.root_ca_check = Some((root_ca_file.to_string(), check));
+                            }
+                            match self.root_ca_check.as_ref().map(|(_, check)| check) {
+                                Some(Ok(count)) => { ui.label(format!("{} certificate(s), trusted alongside the OS store", count)); },
+                                Some(Err(e)) => { ui.colored_label(egui::Color32::RED, e); },
+                                None => (),
+                            }
+                        }
+                        ui.checkbox(&mut self.skip_probe.lock().unwrap(), "Skip connection probe")
+                            .on_hover_text("Send with the clients straight away, without the DNS, TCP and TLS checks in Diagnostics");
+                        ui.checkbox(&mut self.raw_chunk_framing.lock().unwrap(), "Raw chunk framing in probe")
+                            .on_hover_text("Show the chunk-size lines of a chunked probe response in Diagnostics instead of de-chunking it");
+
+                        ui.add_space(10.0);
+
+                        /* Auth */
+                        ui.horizontal(|ui| {
+                            ui.label("Auth:");
+                            let mut auth_type = self.auth_type.lock().unwrap();
+                            egui::ComboBox::from_id_salt("auth_type_combo")
+                                .selected_text(auth_type.as_str())
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut *auth_type, "None".to_string(), "None");
+                                    ui.selectable_value(&mut *auth_type, "Basic".to_string(), "Basic");
+                                    ui.selectable_value(&mut *auth_type, "Bearer".to_string(), "Bearer");
+                                    ui.selectable_value(&mut *auth_type, "Digest".to_string(), "Digest");
+                                });
+                        });
+                        let auth_type = self.auth_type.lock().unwrap().clone();
+                        if auth_type == "Basic" || auth_type == "Digest" {
+                            ui.horizontal(|ui| {
+                                ui.label("Username:");
+                                ui.add(egui::TextEdit::singleline(&mut *self.auth_username.lock().unwrap()).desired_width(120.0));
+                                ui.label("Password:");
+                                ui.add(egui::TextEdit::singleline(&mut *self.auth_password.lock().unwrap()).desired_width(120.0).password(true));
+                            });
+                        }
+                        else if auth_type == "Bearer" {
+                            ui.horizontal(|ui| {
+                                ui.label("Token:");
+                                ui.add(egui::TextEdit::singleline(&mut *self.auth_token.lock().unwrap()).desired_width(ui.available_width()).password(true));
+                            });
+                        }
 
                         ui.add_space(10.0);
 
                         /* Custom Headers */
                         ui.with_layout(egui::Layout::left_to_right(egui::Align::Min), |ui| {
                             ui.label("Headers:");
+                            ui.checkbox(&mut self.exact_headers.lock().unwrap(), "Exact headers only")
+                                .on_hover_text("Don't add a User-Agent. Host, Content-Length and a default Accept: */* are still added by the HTTP client.");
+                            if ui.small_button(format!("Defaults ({})", self.default_headers.len())).on_hover_text("Headers added for every host or for matching hosts").clicked() {
+                                self.show_default_headers = true;
+                            }
                         });
-                        ui.add(
+                        let pasted = ui.input(|i| i.events.iter().any(|event| matches!(event, egui::Event::Paste(_))));
+                        let headers_edit = ui.add(
                             egui::TextEdit::multiline(&mut *self.request_headers.lock().unwrap())
-                                .desired_width(330.0)
+                                .desired_width(f32::INFINITY)
                                 .desired_rows(3)
                                 .hint_text("Content-Type: application/json")
-                        );
+                        ).on_hover_text(format!(
+                            "One header per line, or paste a JSON object of several.\nA comma list like 'Accept: a, b' is sent as typed. Headers that take a comma list: {}",
+                            format::COMMA_COMBINABLE_HEADERS.join(", ")
+                        ));
+                        if pasted && headers_edit.changed() {
+                            let mut headers = self.request_headers.lock().unwrap();
+                            if let Some(lines) = format::headers_from_json(&headers) {
+                                *headers = lines;
+                            }
+                        }
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Accept:");
+                            let mut headers = self.request_headers.lock().unwrap();
+                            let current = format::header_value(&headers, "Accept");
+                            for preset in ACCEPT_PRESETS {
+                                let active = current.as_deref() == Some(preset);
+                                let hover = if active { "Click again to remove the Accept header".to_string() } else { format!("Set Accept: {}", preset) };
+                                if ui.selectable_label(active, preset).on_hover_text(hover).clicked() {
+                                    *headers = format::set_header(&headers, "Accept", (!active).then_some(preset));
+                                }
+                            }
+                        });
+                        for warning in request::header_warnings(&self.request_headers.lock().unwrap()) {
+                            ui.colored_label(egui::Color32::from_rgb(220, 140, 40), warning);
+                        }
+                        if !self.default_headers.is_empty()
+                            && let Ok(url) = self.resolved_url() {
+                            let url = request::normalize_url(&request::apply_default_scheme(&url, &self.default_scheme).unwrap_or(url));
+                            let added = default_headers::added_names(&default_headers::matching(&self.default_headers, &url), &self.request_headers.lock().unwrap());
+                            if !added.is_empty() {
+                                ui.small(format!("Also sent from the defaults: {}", added.join(", ")));
+                            }
+                        }
 
                         ui.add_space(10.0);
 
                         /* Custom Body */
                         ui.with_layout(egui::Layout::left_to_right(egui::Align::Min), |ui| {
                             ui.label("Body:");
+                            ui.checkbox(&mut self.format_on_paste, "Format JSON on paste");
                         });
-                        ui.add(
+                        let body_edit = ui.add(
                             egui::TextEdit::multiline(&mut *self.request_body.lock().unwrap())
-                                .desired_width(330.0)
-                                .desired_rows(10)
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(body_rows)
                                 .hint_text("{\"key\": \"value\"}")
                         );
+                        // Only right after a paste, reformatting while typing would move the cursor around
+                        if self.format_on_paste && pasted && body_edit.changed() {
+                            let mut body = self.request_body.lock().unwrap();
+                            if let Some(pretty) = format::pretty_json(&body) {
+                                *body = pretty;
+                            }
+                        }
+
+                        /* Body Validation */
+                        let content_type = format::header_value(&self.request_headers.lock().unwrap(), "Content-Type").unwrap_or_default().to_ascii_lowercase();
+                        let body = self.request_body.lock().unwrap().clone();
+                        if !body.trim().is_empty() {
+                            if content_type.contains("json") {
+                                match format::validate_json(&body) {
+                                    Ok(_) => {
+                                        ui.horizontal(|ui| {
+                                            ui.colored_label(egui::Color32::GREEN, "Valid JSON");
+                                            ui.checkbox(&mut self.show_body_preview, "Preview formatted");
+                                        });
+                                        if self.show_body_preview {
+                                            body_preview(ui, format::pretty_json(&body).unwrap_or_default());
+                                        }
+                                    },
+                                    Err(e) => {
+                                        ui.colored_label(egui::Color32::RED, format!("Invalid JSON: {}", e));
+                                    },
+                                }
+                            }
+                            else if content_type.contains("xml") {
+                                match format::validate_xml(&body) {
+                                    Ok(_) => {
+                                        ui.horizontal(|ui| {
+                                            ui.colored_label(egui::Color32::GREEN, "Well-formed XML");
+                                            ui.checkbox(&mut self.show_body_preview, "Preview formatted");
+                                        });
+                                        if self.show_body_preview {
+                                            body_preview(ui, format::pretty_xml(&body).unwrap_or_default());
+                                        }
+                                    },
+                                    Err(e) => {
+                                        ui.colored_label(egui::Color32::RED, format!("Invalid XML: {}", e));
+                                    },
+                                }
+                            }
+                            else if content_type.contains("x-www-form-urlencoded") {
+                                egui::CollapsingHeader::new("Form preview")
+                                    .id_salt("form_preview")
+                                    .default_open(true)
+                                    .show(ui, |ui| {
+                                        ui.checkbox(&mut self.decode_display, "Decode")
+                                            .on_hover_text("Off shows the pairs as typed, useful for spotting double-encoding");
+                                        form_grid(ui, "form_preview_grid", if self.decode_display { format::parse_form(&body) } else { format::split_form(&body) });
+                                    });
+                            }
+
+                            let method = self.selected_method();
+                            if request::usually_bodyless(&method) && self.body_warning_dismissed.as_deref() != Some(method.as_str()) {
+                                ui.horizontal(|ui| {
+                                    let outcome = if self.allow_bodyless_body { "it will still be sent" } else { "it will be left out" };
+                                    ui.colored_label(egui::Color32::from_rgb(220, 140, 40), format!("{} requests usually have no body, {}", method, outcome));
+                                    if ui.small_button("Switch to POST").clicked() {
+                                        *self.request_type.lock().unwrap() = "POST".to_string();
+                                    }
+                                    if ui.small_button("Dismiss").clicked() {
+                                        self.body_warning_dismissed = Some(method.clone());
+                                    }
+                                });
+                            }
+                            if body.len() > LARGE_BODY_BYTES {
+                                ui.colored_label(egui::Color32::from_rgb(220, 140, 40), format!("This {} MB body is held in memory and saved with the history, Body from file streams it instead", body.len() / (1024 * 1024)));
+                            }
+                        }
+
+                        /* Body From File */
+                        ui.horizontal(|ui| {
+                            ui.label("Body from file:");
+                            ui.add(egui::TextEdit::singleline(&mut self.body_file).desired_width(250.0).hint_text("/path/to/upload.bin"))
+                                .on_hover_text("Streamed from disk with upload progress instead of the body above, resends read the file again");
+                        });
+                        if !self.body_file.trim().is_empty() {
+                            match std::fs::metadata(self.body_file.trim()) {
+                                Ok(metadata) => { ui.label(format!("{} bytes, sent instead of the typed body", metadata.len())); },
+                                Err(e) => { ui.colored_label(egui::Color32::RED, format!("Unable to read file: {}", e)); },
+                            }
+                        }
 
                         ui.add_space(20.0);
 
                         /* Send/Close Buttons */
                         ui.horizontal(|ui| {
-                            let send_enabled = !self.request_url.lock().unwrap().is_empty() && !is_loading;
+                            let send_enabled = !self.request_url.lock().unwrap().is_empty() && !is_loading && request::parse_method(&self.selected_method()).is_ok() && self.resolved_url().is_ok_and(|url| request::apply_default_scheme(&url, &self.default_scheme).is_ok());
                             
                             if ui.add_enabled(send_enabled, egui::Button::new("Send")).clicked() {
                                 self.show_newrequest = false;    
-                                match self.send_request( self.request_type.lock().unwrap().clone(), self.request_url.lock().unwrap().clone(), self.request_headers.lock().unwrap().clone(), self.request_body.lock().unwrap().clone()) {
+                                match self.send_request(self.selected_method(), self.resolved_url().unwrap_or_default(), self.request_headers.lock().unwrap().clone(), self.request_body.lock().unwrap().clone(), self.request_proxy.lock().unwrap().clone(), self.draft_carry_over()) {
                                     Ok(_) => {
                                         self.ui_error = None;
                                     },
@@ -452,6 +2770,162 @@ impl eframe::App for App {
                 });
         }
 
+        if self.show_encoder {
+            egui::Window::new("Encode / Decode")
+                .open(&mut self.show_encoder)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    egui::ComboBox::from_id_salt("encoder_operation_combo")
+                        .selected_text(self.encoder_operation.as_str())
+                        .show_ui(ui, |ui| {
+                            for operation in format::ENCODINGS {
+                                ui.selectable_value(&mut self.encoder_operation, operation.to_string(), operation);
+                            }
+                        });
+                    ui.label("Input:");
+                    ui.add(egui::TextEdit::multiline(&mut self.encoder_input).code_editor().desired_width(f32::INFINITY).desired_rows(4));
+                    ui.label("Output:");
+                    match format::encode(&self.encoder_operation, &self.encoder_input) {
+                        Ok(output) => {
+                            ui.add(egui::TextEdit::multiline(&mut output.as_str()).code_editor().desired_width(f32::INFINITY).desired_rows(4));
+                            if ui.button("Copy").clicked() {
+                                ui.ctx().copy_text(output);
+                            }
+                        },
+                        Err(e) => { ui.colored_label(egui::Color32::RED, e); },
+                    }
+                });
+        }
+
+        if self.show_replay {
+            let running = *self.replay_running.lock().unwrap();
+            let results = self.replay_results.lock().unwrap().clone();
+            let mut open = true;
+            egui::Window::new("Replay History")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        let enabled = !running && !responses.is_empty();
+                        if ui.add_enabled(enabled, egui::Button::new("Replay All")).on_hover_text("Resend every history entry and compare with the stored response").clicked()
+                            && let Err(e) = self.replay_history() {
+                            self.ui_error = Some(format!("Error replaying history: {}", e));
+                        }
+                        ui.add_enabled(!running, egui::Checkbox::new(&mut self.replay_parallel, "Parallel"));
+                        ui.add_enabled(!running && self.replay_parallel, egui::DragValue::new(&mut self.replay_concurrency).range(1..=64).prefix("Concurrency: "))
+                            .on_hover_text("Maximum requests in flight at once");
+                        if running {
+                            ui.spinner();
+                        }
+                        let mismatches = results.iter().filter(|outcome| !outcome.differences.is_empty()).count();
+                        ui.label(format!("{} / {} replayed, {} mismatched", results.len(), self.replay_total, mismatches));
+                    });
+                    ui.add_space(10.0);
+
+                    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                        egui::Grid::new("replay_grid").striped(true).show(ui, |ui| {
+                            for outcome in &results {
+                                method_badge(ui, &outcome.method);
+                                ui.label(&outcome.url);
+                                if outcome.differences.is_empty() {
+                                    badge(ui, "match", egui::Color32::from_rgb(60, 140, 60));
+                                    ui.label("");
+                                }
+                                else {
+                                    badge(ui, "mismatch", egui::Color32::from_rgb(180, 60, 60));
+                                    ui.label(outcome.differences.join("\n"));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+            self.show_replay = open;
+        }
+
+        if let Some(mut resend) = self.resend_override.take() {
+            let mut open = true;
+            let mut close = false;
+            egui::Window::new("Resend with Changes")
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        method_badge(ui, &resend.method);
+                        ui.label(&resend.url);
+                    });
+                    ui.add_space(10.0);
+                    ui.label("Headers:");
+                    ui.add(egui::TextEdit::multiline(&mut resend.req_headers).desired_width(330.0).desired_rows(3));
+                    ui.label("Body:");
+                    ui.add(egui::TextEdit::multiline(&mut resend.req_body).desired_width(330.0).desired_rows(6));
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(!is_loading, egui::Button::new("Send")).clicked() {
+                            match self.send_request(resend.method.clone(), resend.url.clone(), resend.req_headers.clone(), resend.req_body.clone(), resend.proxy.clone(), resend.carry_over()) {
+                                Ok(_) => self.ui_error = None,
+                                Err(e) => self.ui_error = Some(format!("Error sending request: {}", e)),
+                            }
+                            close = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+            if open && !close {
+                self.resend_override = Some(resend);
+            }
+        }
+
+        // Live events while a text/event-stream response is being read
+        let events = self.event_stream.events.lock().unwrap().clone();
+        if is_loading && !events.is_empty() {
+            egui::Window::new("Event Stream")
+                .collapsible(true)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(format!("{} events", events.len()));
+                        if ui.button("Stop").clicked() {
+                            self.event_stream.cancel.store(true, std::sync::atomic::Ordering::SeqCst);
+                        }
+                    });
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            for event in &events {
+                                ui.label(egui::RichText::new(event).monospace());
+                            }
+                        });
+                });
+            // Events arrive off the UI thread
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+
+        // Runs while closed too, so a token that arrives after closing the window is still stored
+        let options = self.current_options();
+        self.oauth.show(ctx, &mut self.show_oauth, &mut self.environments, &self.active_environment, options);
+        if self.show_saved {
+            match saved::show_manager(ctx, &mut self.show_saved, &mut self.saved_requests) {
+                Some(saved::Action::Load(request)) => {
+                    self.load_draft(&request.method, &request.url, &request.headers, &request.body);
+                    self.show_newrequest = true;
+                },
+                Some(saved::Action::Send(position)) => self.send_saved(position),
+                None => (),
+            }
+        }
+        if self.show_environments {
+            environment::show_manager(ctx, &mut self.show_environments, &mut self.environments);
+        }
+        if self.show_default_headers {
+            default_headers::show_manager(ctx, &mut self.show_default_headers, &mut self.default_headers);
+        }
+
         if !self.set_focus.is_empty() {
             self.set_focus = String::new();
         }
@@ -462,6 +2936,185 @@ impl eframe::App for App {
     }
 }
 
+async fn replay_entry(entry: RequestResult, auth: auth::Auth, options: request::RequestOptions, log_path: Option<&str>, task_error: &Mutex<Option<String>>) -> ReplayOutcome {
+    let options = request::RequestOptions {
+        proxy: entry.proxy.clone(),
+        first_client: entry.first_client.clone(),
+        drop_bodyless_body: entry.drop_bodyless_body,
+        body_file: entry.body_file.clone(),
+        ..options
+    };
+    let started = std::time::Instant::now();
+    let result = request::send_request(entry.method.clone(), entry.url.clone(), entry.req_headers.clone(), entry.req_body.clone(), auth, options).await;
+    if let Some(path) = log_path {
+        let (status, error) = match &result {
+            Ok(response) => (response.status.clone(), None),
+            Err((e, status, ..)) => (status.clone(), Some(e.to_string())),
+        };
+        if let Err(e) = append_log(path, &entry.method, &entry.url, &status, started.elapsed().as_millis() as u64, error.as_deref()) {
+            *task_error.lock().unwrap() = Some(e);
+        }
+    }
+    let differences = match (result, &entry.error) {
+        (Ok(response), None) => format::compare_responses(&entry.status, &entry.body, &response.status, &response.body),
+        (Ok(response), Some(_)) => vec![format!("Previously failed, now returns {}", response.status)],
+        (Err((e, ..)), Some(previous)) if e.to_string() == *previous => Vec::new(),
+        (Err((e, ..)), _) => vec![format!("Request failed: {}", e)],
+    };
+
+    ReplayOutcome {
+        method: entry.method,
+        url: entry.url,
+        differences,
+    }
+}
+
+// One JSON line per request, opened for each append so the file can be moved or truncated mid-session
+fn append_log(path: &str, method: &str, url: &str, status: &str, duration_ms: u64, error: Option<&str>) -> Result<(), String> {
+    let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+    let line = serde_json::json!({
+        "timestamp_ms": timestamp,
+        "method": method,
+        "url": url,
+        "status": status,
+        "duration_ms": duration_ms,
+        "error": error,
+    });
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)
+        .map_err(|e| format!("Unable to open log file {}: {}", path, e))?;
+    // A single write per line keeps lines whole when replay workers log at the same time
+    std::io::Write::write_all(&mut file, format!("{}\n", line).as_bytes())
+        .map_err(|e| format!("Unable to write log file {}: {}", path, e))
+}
+
+fn tls_combo(ui: &mut egui::Ui, id: &str, version: &mut String) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(version.as_str())
+        .show_ui(ui, |ui| {
+            for option in std::iter::once("Any").chain(request::TLS_VERSIONS) {
+                ui.selectable_value(version, option.to_string(), option);
+            }
+        });
+}
+
+// "Any" in the UI is the backend default, an empty setting for RequestOptions
+fn tls_setting(version: &str) -> String {
+    if version == "Any" { String::new() } else { version.to_string() }
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_string(),
+    }
+}
+
+// Shared so pooled connections outlive the request that opened them
+// Every request, replay worker and resend shares this runtime, which has one worker thread per core.
+// The DNS/TCP checks in send_request block their worker for up to a few seconds, so replay
+// concurrency above the core count mostly queues there; connections beyond the pool's idle
+// limit are closed after use and reopened on the next request.
+fn runtime() -> Result<tokio::runtime::Handle, std::io::Error> {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    if let Some(rt) = RUNTIME.get() {
+        return Ok(rt.handle().clone());
+    }
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    Ok(RUNTIME.get_or_init(|| rt).handle().clone())
+}
+
+// Form fields one per row, keys and values as given
+fn form_grid(ui: &mut egui::Ui, id: &str, pairs: Vec<(String, String)>) {
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        for (key, value) in pairs {
+            ui.label(key);
+            ui.label(value);
+            ui.end_row();
+        }
+    });
+}
+
+// Read-only formatted copy of the body, what gets sent is always the typed text
+fn body_preview(ui: &mut egui::Ui, mut preview: String) {
+    egui::ScrollArea::vertical()
+        .id_salt("body_preview")
+        .max_height(150.0)
+        .show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut preview)
+                    .desired_width(330.0)
+                    .interactive(false)
+            );
+        });
+}
+
+fn badge(ui: &mut egui::Ui, text: &str, color: egui::Color32) -> egui::Response {
+    egui::Frame::new()
+        .fill(color)
+        .corner_radius(8.0)
+        .inner_margin(egui::Margin::symmetric(6, 1))
+        .show(ui, |ui| {
+            ui.label(egui::RichText::new(text).color(egui::Color32::WHITE).strong().small());
+        })
+        .response
+}
+
+// Production-looking names stand out in red, so a call against prod is never mistaken for dev
+fn environment_badge(ui: &mut egui::Ui, environment: &str) {
+    let name = environment.to_ascii_lowercase();
+    let color = if name.contains("prod") || name.contains("live") { egui::Color32::from_rgb(190, 50, 50) } else { egui::Color32::from_rgb(70, 110, 150) };
+    badge(ui, environment, color).on_hover_text(format!("Sent with environment '{}'", environment));
+}
+
+fn https_only_badge(ui: &mut egui::Ui) {
+    badge(ui, "🔒 HTTPS only", egui::Color32::from_rgb(60, 140, 60))
+        .on_hover_text("Plain HTTP is refused and invalid certificates are never accepted");
+}
+
+// Not an HTTP error, the server answered but the body was cut off on the way
+fn body_read_badge(ui: &mut egui::Ui) {
+    badge(ui, "body read failed", egui::Color32::from_rgb(200, 90, 20))
+        .on_hover_text("The connection failed while reading the body, only the part that arrived is kept");
+}
+
+fn scheme_badge(ui: &mut egui::Ui, response: &RequestResult) {
+    match response.scheme.as_str() {
+        "https" => { badge(ui, "HTTPS", egui::Color32::from_rgb(60, 140, 60)); },
+        "http" if response.auto_http => { badge(ui, "HTTP", egui::Color32::from_rgb(220, 140, 40)).on_hover_text("Sent as plain HTTP, no scheme was given"); },
+        "http" => { badge(ui, "HTTP", egui::Color32::from_rgb(110, 110, 110)); },
+        _ => (),
+    }
+}
+
+// Host (with a non-default port) and optionally the path, without the query. A URL that doesn't
+// parse is its own group
+fn history_group(url: &str, with_path: bool) -> String {
+    let Ok(parsed) = url::Url::parse(url) else { return url.to_string() };
+    let mut key = parsed.host_str().unwrap_or_default().to_string();
+    if let Some(port) = parsed.port() {
+        key.push_str(&format!(":{}", port));
+    }
+    if with_path {
+        key.push_str(parsed.path());
+    }
+    key
+}
+
+fn method_badge(ui: &mut egui::Ui, method: &str) {
+    let color = match method {
+        "GET" => egui::Color32::from_rgb(60, 160, 90),
+        "POST" => egui::Color32::from_rgb(60, 120, 200),
+        "PUT" => egui::Color32::from_rgb(220, 140, 40),
+        "PATCH" => egui::Color32::from_rgb(150, 100, 200),
+        "DELETE" => egui::Color32::from_rgb(200, 60, 60),
+        _ => egui::Color32::GRAY,
+    };
+    badge(ui, method, color);
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
     let options = eframe::NativeOptions {
@@ -472,11 +3125,11 @@ fn main() -> Result<(), Box<dyn Error>> {
     };
 
     match eframe::run_native(
-        "Nancy Web Debugger",
+        APP_NAME,
         options,
-        Box::new(|_cc| {
+        Box::new(|cc| {
             println!("Initialising App...");
-            Ok(Box::new(App::new()))
+            Ok(Box::new(App::new(cc.storage)))
         })
     ) {
         Ok(_) => {
@@ -487,4 +3140,77 @@ fn main() -> Result<(), Box<dyn Error>> {
             Err(e.into())
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_keeps_the_bytes_a_resend_sends_and_the_response_bytes() {
+        use base64::Engine;
+        let typed_body = "{\"name\":  \"caf\u{e9}\",\n \"tags\" : [1,2]}";
+        let response_bytes = vec![0xff, 0xfe, b'{', 0x80, b'}'];
+        let entry: RequestResult = serde_json::from_value(serde_json::json!({
+            "index": 0,
+            "method": "POST",
+            "req_headers": "Content-Type: application/json",
+            "req_body": typed_body,
+            "url": "http://127.0.0.1/",
+            "status": "200",
+            "headers": [],
+            "body": String::from_utf8_lossy(&response_bytes),
+            "trace": "",
+            "error": null,
+            "raw_exchange": "POST / HTTP/1.1\ncontent-type: application/json\n\nHTTP/1.1 200 OK",
+            "body_base64": base64::engine::general_purpose::STANDARD.encode(&response_bytes),
+        })).unwrap();
+
+        // Saved to history and loaded back
+        let entry: RequestResult = serde_json::from_str(&serde_json::to_string(&entry).unwrap()).unwrap();
+        assert_eq!(entry.body_bytes(), response_bytes);
+
+        // Pretty printing is display only, Resend passes req_body as typed and the rebuilt bytes end with it
+        assert_ne!(format::format_body("Pretty", &entry.req_body), typed_body);
+        assert_eq!(entry.req_body, typed_body);
+        let sent = entry.sent_bytes();
+        assert!(sent.starts_with(b"POST / HTTP/1.1\r\ncontent-type: application/json\r\n\r\n"));
+        assert!(sent.ends_with(typed_body.as_bytes()));
+
+        // A body streamed from a file is read from it again, the rebuilt bytes don't stand in for it
+        let entry = RequestResult { body_file: "upload.bin".to_string(), ..entry };
+        assert_eq!(entry.carry_over().body_file, "upload.bin");
+        assert!(entry.sent_bytes().ends_with(b"\r\n\r\n"));
+    }
+
+    #[test]
+    fn busy_flag_is_reset_when_the_task_panics() {
+        let flag = Arc::new(Mutex::new(false));
+        let result = std::panic::catch_unwind(|| {
+            let _busy = BusyGuard::set(&flag);
+            assert!(*flag.lock().unwrap());
+            panic!("request task failed");
+        });
+        assert!(result.is_err());
+        assert!(!*flag.lock().unwrap());
+    }
+
+    #[test]
+    fn moved_entries_land_in_front_of_the_target() {
+        let app = App::new(None);
+        let entry = |index: usize| -> RequestResult {
+            serde_json::from_value(serde_json::json!({
+                "index": index, "req_headers": "", "req_body": "", "url": "", "status": "", "headers": [], "body": "", "trace": "", "error": null,
+            })).unwrap()
+        };
+        *app.request_responses.lock().unwrap() = (0..4).map(entry).collect();
+        let order = |app: &App| app.request_responses.lock().unwrap().iter().map(|r| r.index).collect::<Vec<_>>();
+
+        app.move_response(0, 2);
+        assert_eq!(order(&app), [1, 0, 2, 3]);
+        app.move_response(3, 1);
+        assert_eq!(order(&app), [3, 1, 0, 2]);
+        app.move_response(3, 3);
+        assert_eq!(order(&app), [3, 1, 0, 2]);
+    }
+}
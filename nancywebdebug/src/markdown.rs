@@ -0,0 +1,124 @@
+use eframe::egui;
+
+// A small CommonMark subset: headings, lists, quotes, rules, code fences and
+// **bold**, `code` and [links](url) inline. Anything else shows as plain text
+pub fn show(ui: &mut egui::Ui, text: &str, text_size: f32) {
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        if let Some(fence) = trimmed.strip_prefix("```") {
+            let mut code = Vec::new();
+            for line in lines.by_ref() {
+                if line.trim_start().starts_with("```") {
+                    break;
+                }
+                code.push(line);
+            }
+            egui::Frame::group(ui.style()).fill(ui.visuals().extreme_bg_color).show(ui, |ui| {
+                if !fence.trim().is_empty() {
+                    ui.small(fence.trim());
+                }
+                ui.label(egui::RichText::new(code.join("\n")).monospace().size(text_size));
+            });
+        }
+        else if trimmed.is_empty() {
+            ui.add_space(text_size * 0.5);
+        }
+        else if let Some((level, heading)) = heading(trimmed) {
+            ui.add_space(text_size * 0.3);
+            ui.label(egui::RichText::new(heading).strong().size(text_size * (1.9 - 0.15 * level as f32)));
+        }
+        else if trimmed.chars().all(|c| c == '-' || c == '*' || c == '_' || c == ' ') && trimmed.chars().filter(|c| !c.is_whitespace()).count() >= 3 {
+            ui.separator();
+        }
+        else if let Some(quote) = trimmed.strip_prefix('>') {
+            ui.horizontal_wrapped(|ui| {
+                ui.add_space(text_size);
+                ui.label(egui::RichText::new("▏").weak().size(text_size));
+                inline(ui, quote.trim(), text_size, true);
+            });
+        }
+        else if let Some((marker, item)) = list_item(trimmed) {
+            let indent = (line.len() - trimmed.len()) as f32 * text_size * 0.4;
+            ui.horizontal_wrapped(|ui| {
+                ui.add_space(indent + text_size);
+                ui.label(egui::RichText::new(marker).size(text_size));
+                inline(ui, item, text_size, false);
+            });
+        }
+        else {
+            // Consecutive text lines are one paragraph
+            let mut paragraph = trimmed.to_string();
+            while let Some(next) = lines.peek() {
+                let next = next.trim_start();
+                if next.is_empty() || next.starts_with("```") || next.starts_with('>') || heading(next).is_some() || list_item(next).is_some() {
+                    break;
+                }
+                paragraph.push(' ');
+                paragraph.push_str(next);
+                lines.next();
+            }
+            ui.horizontal_wrapped(|ui| inline(ui, &paragraph, text_size, false));
+        }
+    }
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && rest.starts_with(' ')).then(|| (level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), item));
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    let item = line[digits..].strip_prefix(". ")?;
+    (digits > 0).then(|| (format!("{}.", &line[..digits]), item))
+}
+
+fn inline(ui: &mut egui::Ui, text: &str, text_size: f32, quote: bool) {
+    ui.spacing_mut().item_spacing.x = 0.0;
+    let styled = |text: &str| {
+        let text = egui::RichText::new(text).size(text_size);
+        if quote { text.italics().weak() } else { text }
+    };
+    let mut rest = text;
+    while !rest.is_empty() {
+        let next = ["**", "`", "["].iter().filter_map(|marker| rest.find(marker).map(|position| (position, *marker))).min();
+        let Some((start, marker)) = next else {
+            ui.label(styled(rest));
+            break;
+        };
+        if start > 0 {
+            ui.label(styled(&rest[..start]));
+        }
+        let after = &rest[start + marker.len()..];
+        let consumed = match marker {
+            "**" => after.find("**").map(|end| {
+                ui.label(styled(&after[..end]).strong());
+                end + 2
+            }),
+            "`" => after.find('`').map(|end| {
+                ui.label(egui::RichText::new(&after[..end]).code().size(text_size));
+                end + 1
+            }),
+            _ => after.find("](").and_then(|label_end| {
+                let url_end = after[label_end + 2..].find(')')? + label_end + 2;
+                ui.hyperlink_to(egui::RichText::new(&after[..label_end]).size(text_size), &after[label_end + 2..url_end]);
+                Some(url_end + 1)
+            }),
+        };
+        match consumed {
+            Some(consumed) => rest = &after[consumed..],
+            // No closing marker, so it was just text
+            None => {
+                ui.label(styled(marker));
+                rest = after;
+            },
+        }
+    }
+}
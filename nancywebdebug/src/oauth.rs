@@ -0,0 +1,163 @@
+use crate::environment::{self, Environment};
+use eframe::egui;
+use nancywebdebug::{auth, format, request};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Status and body of the token response, or why the request failed
+type TokenResult = Result<(String, String), String>;
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct OAuthHelper {
+    pub token_url: String,
+    pub client_id: String,
+    pub scope: String,
+    // The secret isn't saved, like the auth settings
+    #[serde(skip)]
+    client_secret: String,
+    #[serde(skip)]
+    running: Arc<Mutex<bool>>,
+    #[serde(skip)]
+    result: Arc<Mutex<Option<TokenResult>>>,
+    #[serde(skip)]
+    response: String,
+    #[serde(skip)]
+    expires_at: Option<Instant>,
+    #[serde(skip)]
+    message: Option<Result<String, String>>,
+}
+
+impl OAuthHelper {
+    fn request_token(&self, options: request::RequestOptions) {
+        let mut body = url::form_urlencoded::Serializer::new(String::new());
+        body.append_pair("grant_type", "client_credentials");
+        body.append_pair("client_id", self.client_id.trim());
+        body.append_pair("client_secret", &self.client_secret);
+        if !self.scope.trim().is_empty() {
+            body.append_pair("scope", self.scope.trim());
+        }
+        let body = body.finish();
+        let token_url = request::normalize_url(self.token_url.trim());
+        let auth = auth::Auth {
+            auth_type: "None".to_string(),
+            username: String::new(),
+            password: String::new(),
+            token: String::new(),
+        };
+        let result = Arc::clone(&self.result);
+
+        let rt = match crate::runtime() {
+            Ok(rt) => rt,
+            Err(e) => {
+                *result.lock().unwrap() = Some(Err(format!("Error building tokio runtime: {}", e)));
+                return;
+            }
+        };
+        let running = crate::BusyGuard::set(&self.running);
+        std::thread::spawn(move || {
+            let _running = running;
+            let outcome = rt.block_on(request::send_request("POST".to_string(), token_url, "Content-Type: application/x-www-form-urlencoded\nAccept: application/json".to_string(), body, auth, options));
+            *result.lock().unwrap() = Some(match outcome {
+                Ok(response) => Ok((response.status, response.body)),
+                Err((e, status, ..)) => Err(format!("{}: {}", status, e)),
+            });
+        });
+    }
+
+    // Picks up a finished token request and stores the token in the active environment
+    fn take_result(&mut self, environments: &mut [Environment], active_environment: &str) {
+        let Some(result) = self.result.lock().unwrap().take() else { return };
+        let (status, body) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                self.message = Some(Err(e));
+                return;
+            }
+        };
+        self.response = format::pretty_json(&body).unwrap_or_else(|| body.clone());
+        self.expires_at = None;
+
+        // Some providers answer as a form instead of JSON
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap_or_else(|_| {
+            format::parse_form(&body).into_iter().map(|(key, value)| (key, serde_json::Value::String(value))).collect()
+        });
+        let Some(token) = value.get("access_token").and_then(|token| token.as_str()) else {
+            self.message = Some(Err(format!("{}: no access_token in the response", status)));
+            return;
+        };
+        self.expires_at = value.get("expires_in")
+            .and_then(|expires| expires.as_u64().or_else(|| expires.as_str().and_then(|expires| expires.parse().ok())))
+            .map(|seconds| Instant::now() + Duration::from_secs(seconds));
+        self.message = Some(match environments.iter_mut().find(|environment| environment.name == active_environment) {
+            Some(environment) => {
+                environment.set_variable("access_token", token.to_string());
+                Ok(format!("Stored as {{{{access_token}}}} in '{}'", environment.name))
+            },
+            None => Err("No active environment to store the token in".to_string()),
+        });
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool, environments: &mut [Environment], active_environment: &str, options: request::RequestOptions) {
+        self.take_result(environments, active_environment);
+        let running = *self.running.lock().unwrap();
+
+        egui::Window::new("OAuth2 Client Credentials")
+            .open(open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                egui::Grid::new("oauth_grid").show(ui, |ui| {
+                    ui.label("Token URL:");
+                    ui.add(egui::TextEdit::singleline(&mut self.token_url).desired_width(300.0).hint_text("https://auth.example.com/oauth/token"));
+                    ui.end_row();
+                    ui.label("Client ID:");
+                    ui.add(egui::TextEdit::singleline(&mut self.client_id).desired_width(300.0));
+                    ui.end_row();
+                    ui.label("Client secret:");
+                    ui.add(egui::TextEdit::singleline(&mut self.client_secret).desired_width(300.0).password(true));
+                    ui.end_row();
+                    ui.label("Scope:");
+                    ui.add(egui::TextEdit::singleline(&mut self.scope).desired_width(300.0).hint_text("Optional"));
+                    ui.end_row();
+                });
+
+                if environment::find(environments, active_environment).is_none() {
+                    ui.colored_label(egui::Color32::YELLOW, "Select an environment in Create Request to store the token in");
+                }
+                ui.horizontal(|ui| {
+                    let enabled = !running && !self.token_url.trim().is_empty() && !self.client_id.trim().is_empty();
+                    if ui.add_enabled(enabled, egui::Button::new("Get token")).clicked() {
+                        self.message = None;
+                        self.request_token(options);
+                    }
+                    if running {
+                        ui.spinner();
+                    }
+                });
+
+                match &self.message {
+                    Some(Ok(message)) => { ui.label(message); },
+                    Some(Err(e)) => { ui.colored_label(egui::Color32::RED, e); },
+                    None => (),
+                }
+                if let Some(expires_at) = self.expires_at {
+                    let remaining = expires_at.saturating_duration_since(Instant::now()).as_secs();
+                    if remaining == 0 {
+                        ui.colored_label(egui::Color32::RED, "Token expired");
+                    }
+                    else {
+                        ui.label(format!("Expires in {}m {}s", remaining / 60, remaining % 60));
+                        ctx.request_repaint_after(Duration::from_secs(1));
+                    }
+                }
+                if !self.response.is_empty() {
+                    ui.label("Token response:");
+                    egui::ScrollArea::vertical().id_salt("oauth_response_scroll").max_height(200.0).show(ui, |ui| {
+                        ui.add(egui::TextEdit::multiline(&mut self.response.as_str()).code_editor().desired_width(f32::INFINITY));
+                    });
+                }
+            });
+        if running {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+// Clients are kept between requests so keep-alive connections can be reused
+static CLIENT_POOL: OnceLock<Mutex<HashMap<String, PooledClient>>> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+pub struct PooledClient {
+    pub client: Client,
+    counter: ConnectionCounter,
+}
+
+impl PooledClient {
+    pub fn connections_opened(&self) -> usize {
+        self.counter.opened.load(Ordering::SeqCst)
+    }
+}
+
+pub fn get_client<F>(key: String, create: F) -> Result<PooledClient, reqwest::Error>
+where
+    F: FnOnce(ConnectionCounter) -> Result<Client, reqwest::Error>,
+{
+    let mut pool = CLIENT_POOL.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    if let Some(pooled) = pool.get(&key) {
+        return Ok(pooled.clone());
+    }
+
+    let counter = ConnectionCounter::default();
+    let pooled = PooledClient {
+        client: create(counter.clone())?,
+        counter,
+    };
+    pool.insert(key, pooled.clone());
+    Ok(pooled)
+}
+
+// Clients for a single request (e.g. through a one-off proxy) that shouldn't stay around
+pub fn one_off_client<F>(create: F) -> Result<PooledClient, reqwest::Error>
+where
+    F: FnOnce(ConnectionCounter) -> Result<Client, reqwest::Error>,
+{
+    let counter = ConnectionCounter::default();
+    Ok(PooledClient {
+        client: create(counter.clone())?,
+        counter,
+    })
+}
+
+// Connector layer that counts every new connection the client establishes
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionCounter {
+    opened: Arc<AtomicUsize>,
+}
+
+impl<S> Layer<S> for ConnectionCounter {
+    type Service = CountedConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CountedConnector {
+            inner,
+            opened: Arc::clone(&self.opened),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CountedConnector<S> {
+    inner: S,
+    opened: Arc<AtomicUsize>,
+}
+
+impl<S, R> Service<R> for CountedConnector<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        self.opened.fetch_add(1, Ordering::SeqCst);
+        self.inner.call(request)
+    }
+}
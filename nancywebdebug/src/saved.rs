@@ -0,0 +1,109 @@
+use eframe::egui;
+use nancywebdebug::format;
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SavedRequest {
+    pub name: String,
+    pub method: String,
+    // As typed, so a path or {{name}} placeholders resolve against the environment active when sending
+    pub url: String,
+    #[serde(default)]
+    pub headers: String,
+    #[serde(default)]
+    pub body: String,
+    // Shown as a one-click button in the favorites bar
+    #[serde(default)]
+    pub pinned: bool,
+    // JSON path in the body set to counter_next on every send, which then goes up by one
+    #[serde(default)]
+    pub counter_path: String,
+    #[serde(default)]
+    pub counter_next: i64,
+}
+
+impl SavedRequest {
+    // The body to send (already substituted) with the counter filled in. The caller moves
+    // counter_next on once the request is actually sent
+    pub fn fill_counter(&self, body: &str) -> Result<String, String> {
+        if self.counter_path.trim().is_empty() {
+            return Ok(body.to_string());
+        }
+        let mut value: serde_json::Value = serde_json::from_str(body)
+            .map_err(|e| format!("The body isn't JSON, so the counter at '{}' can't be set: {}", self.counter_path, e))?;
+        let field = format::json_path_mut(&mut value, &self.counter_path)
+            .ok_or_else(|| format!("No field at '{}' in the body for the counter", self.counter_path))?;
+        *field = self.counter_next.into();
+        // Keeps a pretty-printed body readable in history
+        let body = if body.trim().contains('\n') { serde_json::to_string_pretty(&value) } else { serde_json::to_string(&value) };
+        body.map_err(|e| e.to_string())
+    }
+}
+
+pub enum Action {
+    // Open in New Request, as saved
+    Load(SavedRequest),
+    // Send straight away like the favorites bar, by position
+    Send(usize),
+}
+
+pub fn show_manager(ctx: &egui::Context, open: &mut bool, saved: &mut Vec<SavedRequest>) -> Option<Action> {
+    let mut action = None;
+    egui::Window::new("Saved Requests")
+        .open(open)
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            if saved.is_empty() {
+                ui.label("Nothing saved yet, use Save request on a history entry.");
+                return;
+            }
+            let mut remove = None;
+            let mut duplicate = None;
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("saved_requests_grid").striped(true).show(ui, |ui| {
+                    ui.label("Name");
+                    ui.label("Request");
+                    ui.label("Counter").on_hover_text("Body field set to the next number on every send, e.g. order.sequence");
+                    ui.label("Pin to bar");
+                    ui.end_row();
+
+                    for (position, request) in saved.iter_mut().enumerate() {
+                        ui.add(egui::TextEdit::singleline(&mut request.name).desired_width(120.0));
+                        ui.label(format!("{} {}", request.method, request.url));
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut request.counter_path).desired_width(100.0).hint_text("body path"));
+                            if !request.counter_path.trim().is_empty() {
+                                ui.add(egui::DragValue::new(&mut request.counter_next).prefix("next "));
+                            }
+                        });
+                        ui.checkbox(&mut request.pinned, "");
+                        ui.horizontal(|ui| {
+                            if ui.small_button("Load").on_hover_text("Open in New Request").clicked() {
+                                action = Some(Action::Load(request.clone()));
+                            }
+                            if ui.small_button("Send").on_hover_text("Send now, filling in the counter if it has one").clicked() {
+                                action = Some(Action::Send(position));
+                            }
+                            if ui.small_button("Duplicate").on_hover_text("Copy it, e.g. to give the copy a counter").clicked() {
+                                duplicate = Some(position);
+                            }
+                            if ui.small_button("Remove").clicked() {
+                                remove = Some(position);
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+            if let Some(position) = remove {
+                saved.remove(position);
+            }
+            if let Some(position) = duplicate {
+                let mut copy = saved[position].clone();
+                copy.name = format!("{} (copy)", copy.name);
+                copy.pinned = false;
+                saved.insert(position + 1, copy);
+            }
+        });
+    action
+}
@@ -0,0 +1,104 @@
+use eframe::egui;
+
+pub const THEMES: [&str; 3] = ["System", "Dark", "Light"];
+// Share of the width the left column gets, kept so neither side can be dragged away entirely
+pub const DEFAULT_SPLIT: f32 = 0.5;
+const MIN_SPLIT: f32 = 0.2;
+const MAX_SPLIT: f32 = 0.8;
+
+// Look and layout preferences, stored together under one key
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    // One of THEMES
+    pub theme: String,
+    pub text_size: f32,
+    pub split: f32,
+    // One of HISTORY_DENSITIES
+    pub history_density: String,
+    // Wrap, Collapse or Off for lines too long to lay out quickly
+    pub long_line_mode: String,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        UiSettings {
+            theme: THEMES[0].to_string(),
+            text_size: crate::DEFAULT_TEXT_SIZE,
+            split: DEFAULT_SPLIT,
+            history_density: crate::HISTORY_DENSITIES[0].to_string(),
+            long_line_mode: "Wrap".to_string(),
+        }
+    }
+}
+
+impl UiSettings {
+    // Falls back to the keys these were saved under one by one, so an update keeps them
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        let Some(storage) = storage else { return UiSettings::default() };
+        if let Some(settings) = eframe::get_value::<UiSettings>(storage, "ui_settings") {
+            return settings.clamped();
+        }
+        let defaults = UiSettings::default();
+        UiSettings {
+            text_size: eframe::get_value(storage, "text_size").unwrap_or(defaults.text_size),
+            history_density: eframe::get_value(storage, "history_density").unwrap_or(defaults.history_density.clone()),
+            long_line_mode: eframe::get_value(storage, "long_line_mode").unwrap_or(defaults.long_line_mode.clone()),
+            ..defaults
+        }.clamped()
+    }
+
+    // A hand-edited or older save could hold sizes the controls would never set
+    fn clamped(self) -> Self {
+        UiSettings {
+            text_size: self.text_size.clamp(crate::MIN_TEXT_SIZE, crate::MAX_TEXT_SIZE),
+            split: self.split.clamp(MIN_SPLIT, MAX_SPLIT),
+            ..self
+        }
+    }
+
+    pub fn apply_theme(&self, ctx: &egui::Context) {
+        let theme = match self.theme.as_str() {
+            "Dark" => egui::ThemePreference::Dark,
+            "Light" => egui::ThemePreference::Light,
+            _ => egui::ThemePreference::System,
+        };
+        if ctx.options(|options| options.theme_preference) != theme {
+            ctx.set_theme(theme);
+        }
+    }
+}
+
+// Like ui.columns(2, ..) but split at `split`, with a handle in the gap to drag it
+pub fn split_columns<R>(ui: &mut egui::Ui, split: &mut f32, add_contents: impl FnOnce(&mut [egui::Ui]) -> R) -> R {
+    let spacing = ui.spacing().item_spacing.x;
+    let width = ui.available_width() - spacing;
+    let top_left = ui.cursor().min;
+    let bottom = ui.max_rect().bottom();
+    let widths = [width * *split, width * (1.0 - *split)];
+
+    let mut columns: Vec<egui::Ui> = widths.iter().enumerate()
+        .map(|(column, column_width)| {
+            let left = if column == 0 { top_left.x } else { top_left.x + widths[0] + spacing };
+            let rect = egui::Rect::from_min_max(egui::pos2(left, top_left.y), egui::pos2(left + column_width, bottom));
+            let mut column_ui = ui.new_child(egui::UiBuilder::new().max_rect(rect).layout(egui::Layout::top_down_justified(egui::Align::LEFT)));
+            column_ui.set_width(*column_width);
+            column_ui
+        })
+        .collect();
+    let result = add_contents(&mut columns[..]);
+
+    let height = columns.iter().map(|column| column.min_size().y).fold(0.0, f32::max);
+    let handle = egui::Rect::from_min_max(egui::pos2(top_left.x + widths[0], top_left.y), egui::pos2(top_left.x + widths[0] + spacing, top_left.y + height));
+    let response = ui.interact(handle, ui.id().with("column_split"), egui::Sense::drag())
+        .on_hover_cursor(egui::CursorIcon::ResizeHorizontal);
+    if response.dragged() && width > 0.0 {
+        *split = (*split + response.drag_delta().x / width).clamp(MIN_SPLIT, MAX_SPLIT);
+    }
+    if response.hovered() || response.dragged() {
+        ui.painter().vline(handle.center().x, handle.y_range(), ui.visuals().widgets.hovered.fg_stroke);
+    }
+
+    ui.advance_cursor_after_rect(egui::Rect::from_min_size(top_left, egui::vec2(ui.available_width(), height)));
+    result
+}